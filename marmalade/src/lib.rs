@@ -26,5 +26,6 @@ pub mod input;
 pub mod net;
 pub mod rand;
 pub mod render;
+pub mod storage;
 pub mod tick_scheduler;
 pub mod time;
@@ -1,4 +1,5 @@
 mod web_socket;
 
+pub use web_socket::Message;
 pub use web_socket::WebSocket;
 pub use web_socket::WebSocketError;
@@ -0,0 +1,26 @@
+use crate::dom::window;
+use web_sys::Storage;
+
+pub struct StorageError;
+
+fn local_storage() -> Option<Storage> {
+    window().local_storage().ok().flatten()
+}
+
+/// Read a value from the browser local storage
+#[must_use]
+pub fn get(key: &str) -> Option<String> {
+    local_storage()?.get_item(key).ok().flatten()
+}
+
+/// Write a value to the browser local storage
+///
+/// # Errors
+///
+/// Returns Err if the storage is unavailable or full
+pub fn set(key: &str, value: &str) -> Result<(), StorageError> {
+    local_storage()
+        .ok_or(StorageError)?
+        .set_item(key, value)
+        .map_err(|_| StorageError)
+}
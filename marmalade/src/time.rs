@@ -1,5 +1,6 @@
 use crate::dom::window;
 use futures_channel::oneshot;
+use js_sys::Date;
 use std::time::Duration;
 use wasm_bindgen::{prelude::Closure, JsCast};
 
@@ -18,3 +19,9 @@ pub async fn sleep(duration: &Duration) {
 
     recv.await.unwrap();
 }
+
+/// Milliseconds elapsed since the Unix epoch, according to the browser clock
+#[must_use]
+pub fn unix_millis() -> f64 {
+    Date::now()
+}
@@ -0,0 +1,86 @@
+use std::{cell::RefCell, rc::Rc};
+
+use marmalade::net::{Message, WebSocket};
+
+/// A websocket polled every frame, the game never waits on the network
+pub enum Connection {
+    /// The socket is written here once it opened, or failed to
+    Connecting(Rc<RefCell<Option<Result<WebSocket, ()>>>>),
+    Open(WebSocket),
+    Closed,
+}
+
+impl Connection {
+    pub fn open(url: String) -> Self {
+        let slot = Rc::new(RefCell::new(None));
+
+        let pending = slot.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let socket = WebSocket::new(&url).await;
+            *pending.borrow_mut() = Some(socket);
+        });
+
+        Self::Connecting(slot)
+    }
+
+    /// True on the frame the socket opened, Err on the frame it failed to
+    pub fn poll_open(&mut self) -> Result<bool, ()> {
+        let Self::Connecting(slot) = self else {
+            return Ok(false);
+        };
+        let Some(result) = slot.borrow_mut().take() else {
+            return Ok(false);
+        };
+
+        match result {
+            Ok(socket) => {
+                *self = Self::Open(socket);
+                Ok(true)
+            }
+            Err(()) => {
+                *self = Self::Closed;
+                Err(())
+            }
+        }
+    }
+
+    /// Text messages received since the last call, Err on the frame the connection was lost
+    pub fn receive(&mut self) -> Result<Vec<String>, ()> {
+        let Self::Open(socket) = self else {
+            return Ok(Vec::new());
+        };
+
+        let mut messages = Vec::new();
+        loop {
+            match socket.read() {
+                Ok(Some(Message::Text(text))) => messages.push(text),
+                Ok(Some(Message::Binary(_))) => {}
+                Ok(None) => return Ok(messages),
+                Err(_) => {
+                    *self = Self::Closed;
+                    return Err(());
+                }
+            }
+        }
+    }
+
+    pub fn send(&mut self, text: &str) -> Result<(), ()> {
+        let Self::Open(socket) = self else {
+            return Err(());
+        };
+        if socket.send_str(text).is_err() {
+            *self = Self::Closed;
+            return Err(());
+        }
+        Ok(())
+    }
+
+    pub fn close(&mut self) {
+        if let Self::Open(socket) = self
+            && socket.is_open()
+        {
+            socket.close();
+        }
+        *self = Self::Closed;
+    }
+}
@@ -1,85 +1,704 @@
-use entity::BallType;
+use connection::Connection;
 use glam::Mat3;
 use glam::Vec2;
 use glam::Vec4;
 use marmalade::audio;
+use marmalade::console;
 use marmalade::dom_stack;
 use marmalade::draw_scheduler;
 use marmalade::input;
 use marmalade::input::Button;
 use marmalade::input::Key;
+use marmalade::rand;
 use marmalade::render::canvas2d::Canvas2d;
 use marmalade::render::canvas2d::DrawTarget2d;
 use marmalade::render::canvas2d::TextureRect;
 use marmalade::render::color;
+use marmalade::storage;
 use marmalade::tick_scheduler::TickScheduler;
+use marmalade::time;
 use resources::Resources;
 use std::collections::BTreeMap;
+use std::task::Poll;
 use std::time::Duration;
-use world::Sounds;
-use world::WORLD_DIM;
-use world::World;
-
-mod entity;
+use upsi5_core::achievements::Achievement;
+use upsi5_core::achievements::AchievementProgress;
+use upsi5_core::achievements::AchievementRegistry;
+use upsi5_core::achievements::AchievementTracker;
+use upsi5_core::daily::DailyChallenge;
+use upsi5_core::daily::DailyResult;
+use upsi5_core::daily::Date;
+use upsi5_core::entity::Archetype;
+use upsi5_core::entity::Ball;
+use upsi5_core::entity::BallId;
+use upsi5_core::entity::BallType;
+use upsi5_core::entity::PlayerId;
+use upsi5_core::events::Event;
+use upsi5_core::ghost::Ghost;
+use upsi5_core::items::ItemKind;
+use upsi5_core::leaderboard;
+use upsi5_core::leaderboard::LeaderboardRequest;
+use upsi5_core::leaderboard::LeaderboardResponse;
+use upsi5_core::leaderboard::Score;
+use upsi5_core::obstacles;
+use upsi5_core::obstacles::Obstacle;
+use upsi5_core::online;
+use upsi5_core::online::ClientMessage;
+use upsi5_core::online::ServerMessage;
+use upsi5_core::preview::Preview;
+use upsi5_core::replay::Playback;
+use upsi5_core::replay::Replay;
+use upsi5_core::save::SaveData;
+use upsi5_core::scoring::ComboKind;
+use upsi5_core::scoring::RoundScore;
+use upsi5_core::solver::{Search, Solver};
+use upsi5_core::table::TableRegistry;
+use upsi5_core::upgrades::Upgrade;
+use upsi5_core::upgrades::UpgradeLevels;
+use upsi5_core::upgrades::UpgradeRegistry;
+use upsi5_core::world;
+use upsi5_core::world::Snapshot;
+use upsi5_core::world::Sounds;
+use upsi5_core::world::World;
+
+mod connection;
 mod resources;
-mod world;
 
+/// Playing area the screen layout is designed around, other tables are scaled to take its place
+const LAYOUT_DIM: Vec2 = Vec2::new(1.926, 1.01);
 const BORDER_SIZE: f32 = 0.068;
 
-const PRICE_MAX_SPEED: [u64; 4] = [500, 1500, 3000, 5000];
-const PRICE_START_MASS: [u64; 4] = [500, 1500, 3000, 5000];
-const PRICE_AIM_ASSIST: [u64; 3] = [500, 1500, 3000];
-const PRICE_PROFITABILITY: [u64; 2] = [500, 1500];
-const PRICE_SLIDING: [u64; 4] = [500, 1500, 3000, 5000];
-
 const ICON_SIZE: Vec2 = Vec2::splat(0.2);
 const ICON_SPACE: Vec2 = Vec2::splat(ICON_SIZE.x + 0.15);
 const BUTTON_SIZE: Vec2 = Vec2::new(0.2, 0.06);
 const BUTTON_SPACE: f32 = 0.1;
 const BUTTON_FONT_SIZE: f32 = 0.04;
 
-const AIM_ASSIST_LENGTH: f32 = WORLD_DIM.x / 3.;
-
 const TEXT_COLOR: Vec4 = color::WHITE;
 
 const ASPECT_RATIO: f32 = 1.5;
 
-fn game_tick(game: &mut Game, resources: &mut Resources) {
-    if game.state == GameState::Running {
-        let (run, sounds) = game.world.tick();
-        if !run {
-            game.state = GameState::Playing;
-            game.world.spawn_round();
+const FAST_FORWARD_SPEED: u32 = 8;
+
+/// Impulse of a hit played at full volume, softer hits are quieter
+const LOUD_IMPULSE: f32 = 0.003;
+const QUIETEST_SOUND: f32 = 0.2;
+
+const SAVE_KEY: &str = "spool_save";
+const PLAYER_NAME_KEY: &str = "spool_player_name";
+
+/// Best scores listed on the game over screen
+const LEADERBOARD_SHOWN: usize = 5;
+/// Keys picking a run of the leaderboard to race, one per listed score
+const LEADERBOARD_KEYS: [Key; LEADERBOARD_SHOWN] = [
+    Key::Digit1,
+    Key::Digit2,
+    Key::Digit3,
+    Key::Digit4,
+    Key::Digit5,
+];
+
+/// Ticks a score popup floats over the table for
+const POPUP_TICKS: u32 = 900;
+/// Distance a popup rises each tick
+const POPUP_RISE: f32 = 0.00005;
+
+/// How long an unlocked achievement stays on screen
+const TOAST_DURATION_MS: f64 = 4000.;
+
+/// Tint of the balls of the raced run
+const GHOST_COLOR: Vec4 = Vec4::new(1., 1., 1., 0.35);
+
+const POWER_UP_COLOR: Vec4 = Vec4::new(0.5, 0.85, 1., 1.);
+
+const VERSUS_PLAYERS: u8 = 2;
+/// Room every online player joins, the relay pairs them in the order they come
+const ONLINE_ROOM: &str = "public";
+/// Ticks of the hint search played each frame
+const HINT_TICKS_PER_FRAME: usize = 2000;
+/// Colour of each player in versus runs, matching their slimes
+const PLAYER_COLORS: [Vec4; VERSUS_PLAYERS as usize] =
+    [Vec4::new(0.45, 1., 0.45, 1.), Vec4::new(0.45, 0.45, 1., 1.)];
+
+fn new_seed() -> u64 {
+    (rand::rand() * u64::MAX as f64) as u64
+}
+
+fn new_run(game: &mut Game) {
+    let tables = TableRegistry::builtin();
+
+    // Races are played on the seed and table of the ghost, with the upgrades of the player
+    game.ghost = game.next_ghost.take();
+    let (seed, table) = match &game.ghost {
+        Some(ghost) => (ghost.replay().seed, ghost.replay().table.clone()),
+        None => (
+            new_seed(),
+            tables
+                .get(&game.table)
+                .unwrap_or_else(|| tables.default_table())
+                .clone(),
+        ),
+    };
+
+    game.world = World::new(seed, &game.levels, &table);
+    game.replay = Replay::new(seed, game.levels.clone(), &table);
+    game.daily_run = false;
+    game.versus = false;
+    leave_online(game);
+    game.turn = PlayerId(0);
+    reset_undos(game);
+    game.achievements.start_run(&game.levels);
+    game.round_score = None;
+}
+
+/// Start a hot-seat run where two players take turns on the same table
+fn start_versus(game: &mut Game) {
+    let seed = new_seed();
+
+    let tables = TableRegistry::builtin();
+    let table = tables
+        .get(&game.table)
+        .unwrap_or_else(|| tables.default_table());
+
+    game.world = World::with_players(seed, &game.levels, table, VERSUS_PLAYERS);
+    game.replay = Replay::with_players(seed, game.levels.clone(), table, VERSUS_PLAYERS);
+    game.ghost = None;
+    game.daily_run = false;
+    game.versus = true;
+    leave_online(game);
+    game.turn = PlayerId(0);
+    reset_undos(game);
+}
+
+/// Connect to the relay, the match starts once it found an opponent
+fn start_online(game: &mut Game) {
+    game.online = Some(Online {
+        connection: Connection::open(format!("ws://localhost:{}", online::DEFAULT_RELAY_PORT)),
+        waiting: false,
+        status: "Connecting to the relay".to_owned(),
+    });
+    game.state = GameState::Lobby;
+}
+
+fn leave_online(game: &mut Game) {
+    if let Some(mut online) = game.online.take() {
+        online.connection.close();
+    }
+}
+
+fn send(online: &mut Online, message: &ClientMessage) {
+    if online.connection.send(&message.to_json()).is_err() {
+        online.status = "Lost the connection to the relay".to_owned();
+    }
+}
+
+/// Send the planned shots of this player, the round starts once every player sent theirs
+fn submit_moves(game: &mut Game) {
+    let round = game.world.round;
+    let shots = online::shots(&game.moves);
+    if let Some(online) = &mut game.online {
+        send(online, &ClientMessage::Moves { round, shots });
+        online.waiting = true;
+    }
+}
+
+/// Read the messages of the relay and apply them to the game
+fn poll_online(game: &mut Game, resources: &Resources) {
+    let Some(online) = &mut game.online else {
+        return;
+    };
+
+    match online.connection.poll_open() {
+        Ok(true) => {
+            online.status = "Waiting for an opponent".to_owned();
+            send(
+                online,
+                &ClientMessage::Join {
+                    room: ONLINE_ROOM.to_owned(),
+                    version: online::PROTOCOL_VERSION,
+                },
+            );
         }
-        if game.world.is_game_over() {
-            game.state = GameState::GameOver;
-            game.total_money += game.world.money;
-            game.world.money = 0;
+        Ok(false) => {}
+        Err(()) => online.status = "Couldn't reach the relay".to_owned(),
+    }
+
+    let messages = match online.connection.receive() {
+        Ok(messages) => messages,
+        Err(()) => {
+            online.status = "Lost the connection to the relay".to_owned();
+            if game.state != GameState::Lobby {
+                game.state = GameState::GameOver;
+            }
+            return;
+        }
+    };
 
-            game.best_round = game.best_round.max(game.world.round);
+    for message in messages {
+        match ServerMessage::from_json(&message) {
+            Ok(message) => handle_server_message(game, message, resources),
+            Err(e) => console::warn(&e.to_string()),
         }
-        for sound in sounds {
-            match sound {
-                Sounds::SlimeSlime => {
-                    audio::play(&resources.sounds_slimeslime, 1.);
+    }
+}
+
+/// Send the score of the run that just ended, then fetch the leaderboard to show it
+fn submit_score(game: &mut Game, score: Score) {
+    let name = score.name.clone();
+    game.leaderboard = Some(LeaderboardView {
+        connection: Connection::open(format!(
+            "ws://localhost:{}",
+            leaderboard::DEFAULT_LEADERBOARD_PORT
+        )),
+        queued: vec![
+            LeaderboardRequest::Submit {
+                score,
+                replay: game.replay.to_bytes(),
+            },
+            LeaderboardRequest::Top {
+                count: LEADERBOARD_SHOWN,
+            },
+            LeaderboardRequest::Rank { name },
+        ],
+        top: Vec::new(),
+        rank: None,
+        status: "Sending the score".to_owned(),
+    });
+}
+
+fn close_leaderboard(game: &mut Game) {
+    if let Some(mut view) = game.leaderboard.take() {
+        view.connection.close();
+    }
+}
+
+fn poll_leaderboard(game: &mut Game) {
+    let Some(view) = &mut game.leaderboard else {
+        return;
+    };
+
+    match view.connection.poll_open() {
+        Ok(true) => {
+            for request in std::mem::take(&mut view.queued) {
+                if view.connection.send(&request.to_json()).is_err() {
+                    view.status = "Leaderboard unavailable".to_owned();
                 }
-                Sounds::Coin => {
-                    audio::play(&resources.sounds_coin, 1.);
+            }
+        }
+        Ok(false) => {}
+        Err(()) => view.status = "Leaderboard unavailable".to_owned(),
+    }
+
+    let Ok(messages) = view.connection.receive() else {
+        view.status = "Leaderboard unavailable".to_owned();
+        return;
+    };
+    for message in messages {
+        match LeaderboardResponse::from_json(&message) {
+            Ok(LeaderboardResponse::Submitted { .. }) => view.status.clear(),
+            Ok(LeaderboardResponse::Rejected { reason }) => {
+                view.status = format!("Score rejected: {reason}");
+            }
+            Ok(LeaderboardResponse::Top { scores }) => view.top = scores,
+            Ok(LeaderboardResponse::Rank { rank, .. }) => view.rank = rank,
+            Ok(LeaderboardResponse::Replay { score, replay }) => {
+                match Replay::from_bytes(&replay) {
+                    Ok(replay) => {
+                        view.status = format!("Racing {} next run", score.name);
+                        game.next_ghost = Some(Ghost::new(score.name, replay));
+                    }
+                    Err(e) => view.status = format!("Can't race {}: {e}", score.name),
                 }
-                Sounds::Border => {
-                    audio::play(&resources.sounds_border, 3.);
+            }
+            Ok(LeaderboardResponse::Error { message }) => view.status = message,
+            Err(e) => console::warn(&e.to_string()),
+        }
+    }
+}
+
+/// Ask the leaderboard for the replay of a listed run, raced once it arrived
+fn request_ghost(game: &mut Game, index: usize) {
+    let Some(view) = &mut game.leaderboard else {
+        return;
+    };
+    let Some(score) = view.top.get(index) else {
+        return;
+    };
+
+    let request = LeaderboardRequest::Replay {
+        name: score.name.clone(),
+    };
+    if view.connection.send(&request.to_json()).is_err() {
+        view.status = "Leaderboard unavailable".to_owned();
+    }
+}
+
+/// Show the achievements just unlocked and save them
+fn unlock(game: &mut Game, unlocked: Vec<&'static Achievement>) {
+    if unlocked.is_empty() {
+        return;
+    }
+
+    let until = time::unix_millis() + TOAST_DURATION_MS;
+    game.toasts
+        .extend(unlocked.into_iter().map(|achievement| (achievement, until)));
+    save_progress(game);
+}
+
+/// Let the ghost play its shots of the round the player just launched
+fn launch_ghost(game: &mut Game) {
+    if let Some(ghost) = &mut game.ghost
+        && let Err(e) = ghost.launch(game.world.round)
+    {
+        console::warn(&format!("Ghost stopped: {e}"));
+        game.ghost = None;
+    }
+}
+
+/// Name the scores of this browser are submitted under, made up on the first run
+fn load_player_name() -> String {
+    if let Some(name) = storage::get(PLAYER_NAME_KEY) {
+        return name;
+    }
+
+    let name = format!("Slime{:04}", new_seed() % 10_000);
+    if storage::set(PLAYER_NAME_KEY, &name).is_err() {
+        console::warn("Couldn't write the player name to the local storage");
+    }
+    name
+}
+
+fn handle_server_message(game: &mut Game, message: ServerMessage, resources: &Resources) {
+    let Some(online) = &mut game.online else {
+        return;
+    };
+
+    match message {
+        ServerMessage::Start { setup, you } => match setup.replay() {
+            Ok(replay) => {
+                online.status.clear();
+                game.world = replay.world();
+                game.replay = replay;
+                game.ghost = None;
+                game.daily_run = false;
+                game.versus = false;
+                game.turn = you;
+                game.moves.clear();
+                // Shots can't be taken back once the other players saw them
+                game.undos_left = 0;
+                game.last_shot = None;
+                game.state = GameState::Playing;
+            }
+            Err(e) => online.status = e.to_string(),
+        },
+        ServerMessage::Round { round, shots } => {
+            if round != game.world.round {
+                online.status = format!("Got round {round} while at round {}", game.world.round);
+                return;
+            }
+            online.waiting = false;
+            game.moves.clear();
+
+            // A client only shooting its own slimes simulates the same round as everyone else
+            let seats = match online::launch_round(&mut game.world, &shots) {
+                Ok(seats) => seats,
+                Err(e) => {
+                    online.status =
+                        format!("Out of sync with the other player since round {round}: {e}");
+                    return;
                 }
-                Sounds::EnemyEnemy => {
-                    audio::play(&resources.sounds_enemyenemy, 1.);
+            };
+            for (seat, moves) in seats.iter().enumerate() {
+                game.replay.record(round, PlayerId(seat as u8), moves);
+            }
+            game.round_score = None;
+            game.state = GameState::Running;
+            audio::play(&resources.sounds_shot, 1.);
+        }
+        ServerMessage::Desync { round } => {
+            online.status = format!("Out of sync with the other player since round {round}");
+        }
+        ServerMessage::Left { player } => {
+            online.status = format!("Player {} left", player.0 + 1);
+            game.state = GameState::GameOver;
+        }
+        ServerMessage::Error { message } => {
+            console::warn(&format!("Relay: {message}"));
+            online.status = message;
+        }
+    }
+}
+
+/// Hand the table to the next player who still has a slime
+fn next_turn(game: &mut Game) {
+    let players = game.world.players();
+    for step in 1..=players {
+        let player = PlayerId((game.turn.0 + step) % players);
+        let has_slime = game
+            .world
+            .balls
+            .iter()
+            .any(|ball| ball.borrow().letypedelaboule == BallType::Player(player));
+        if has_slime {
+            game.turn = player;
+            return;
+        }
+    }
+}
+
+/// Take the last shot back and plan it again
+fn undo(game: &mut Game) {
+    if game.undos_left == 0 {
+        return;
+    }
+    let Some((snapshot, moves, turn, achievements)) = game.last_shot.take() else {
+        return;
+    };
+
+    game.world.restore(snapshot);
+    game.achievements.progress = achievements;
+    game.turn = turn;
+    game.replay.rounds.pop();
+    game.moves = moves;
+    game.undos_left -= 1;
+    game.state = GameState::Playing;
+}
+
+fn reset_undos(game: &mut Game) {
+    game.undos_left = UpgradeRegistry::builtin().stats(&game.replay.levels).undos;
+    game.last_shot = None;
+}
+
+/// Start today's challenge, false if it was already attempted
+fn start_daily(game: &mut Game) -> bool {
+    let today = Date::from_unix_millis(time::unix_millis());
+    if game.daily.is_some_and(|daily| daily.date == today) {
+        return false;
+    }
+
+    let challenge = DailyChallenge::new(today);
+    game.world = World::new(challenge.seed, &challenge.levels, challenge.table);
+    game.replay = Replay::new(challenge.seed, challenge.levels, challenge.table);
+    game.ghost = None;
+    game.daily_run = true;
+    reset_undos(game);
+    game.achievements.start_run(&game.replay.levels);
+
+    // The attempt counts as soon as it starts, leaving the page doesn't give a new one
+    game.daily = Some(DailyResult {
+        date: today,
+        round: 0,
+        money: 0,
+    });
+    save_progress(game);
+    true
+}
+
+fn save_progress(game: &Game) {
+    let save = SaveData {
+        total_money: game.total_money,
+        best_round: game.best_round,
+        levels: game.levels.clone(),
+        daily: game.daily,
+        last_run: game.last_run.clone(),
+        achievements: game.achievements.progress.clone(),
+    };
+
+    if storage::set(SAVE_KEY, &save.to_json()).is_err() {
+        console::warn("Couldn't write the save to the local storage");
+    }
+}
+
+fn load_progress() -> SaveData {
+    match storage::get(SAVE_KEY).map(|json| SaveData::from_json(&json)) {
+        Some(Ok(save)) => save,
+        Some(Err(e)) => {
+            console::warn(&format!("Ignoring the save: {e}"));
+            SaveData::default()
+        }
+        None => SaveData::default(),
+    }
+}
+
+/// Play each sound once per tick, louder for the hardest hit that caused it
+fn play_sounds(events: &[Event], resources: &Resources) {
+    let mut sounds: BTreeMap<Sounds, f32> = BTreeMap::new();
+    for event in events {
+        if let Some(sound) = event.sound() {
+            let impulse = event.impulse();
+            let loudness = if impulse > 0. {
+                (impulse / LOUD_IMPULSE).clamp(QUIETEST_SOUND, 1.)
+            } else {
+                1.
+            };
+            let louder = sounds.entry(sound).or_default();
+            *louder = louder.max(loudness);
+        }
+    }
+
+    for (sound, loudness) in sounds {
+        match sound {
+            Sounds::SlimeSlime => {
+                audio::play(&resources.sounds_slimeslime, loudness);
+            }
+            Sounds::Coin => {
+                audio::play(&resources.sounds_coin, loudness);
+            }
+            Sounds::Border => {
+                audio::play(&resources.sounds_border, 3. * loudness);
+            }
+            Sounds::EnemyEnemy => {
+                audio::play(&resources.sounds_enemyenemy, loudness);
+            }
+            Sounds::SlimeEnemy => {
+                audio::play(&resources.sounds_slimeenemy, loudness);
+            }
+            Sounds::Falling => {
+                audio::play(&resources.sounds_falling, 2. * loudness);
+            }
+        }
+    }
+}
+
+/// Float the money of the coins, the combos and the power-ups above where they happened
+fn spawn_popups(game: &mut Game, events: &[Event]) {
+    for event in events {
+        match *event {
+            Event::CoinCollected {
+                position, value, ..
+            } => game.popups.push(Popup {
+                text: format!("+{value}"),
+                position,
+                color: TEXT_COLOR,
+                age: 0,
+            }),
+            Event::Combo {
+                kind,
+                position,
+                multiplier,
+            } => {
+                let name = match kind {
+                    ComboKind::Pocket => "Multi pocket",
+                    ComboKind::Collector => "Both halves",
+                    ComboKind::BankShot => "Bank shot",
+                };
+                game.popups.push(Popup {
+                    text: format!("{name} x{multiplier}"),
+                    position,
+                    color: color::rgb(1., 1., 0.),
+                    age: 0,
+                });
+            }
+            Event::PowerUpCollected { kind, position, .. } => game.popups.push(Popup {
+                text: power_up_name(kind).to_owned(),
+                position,
+                color: POWER_UP_COLOR,
+                age: 0,
+            }),
+            Event::RoundScored { score } => game.round_score = Some(score),
+            _ => {}
+        }
+    }
+}
+
+fn game_tick(game: &mut Game, resources: &mut Resources) {
+    for popup in &mut game.popups {
+        popup.age += 1;
+    }
+    game.popups.retain(|popup| popup.age < POPUP_TICKS);
+
+    if game.state == GameState::Replaying
+        && let Some(viewer) = &mut game.viewer
+        && (!viewer.paused || viewer.stepping)
+    {
+        let ticks = if viewer.fast_forward {
+            FAST_FORWARD_SPEED
+        } else {
+            1
+        };
+
+        for _ in 0..ticks {
+            match viewer.playback.tick() {
+                Ok((round_ended, events)) => {
+                    if !viewer.fast_forward {
+                        play_sounds(&events, resources);
+                    }
+                    if round_ended && viewer.stepping {
+                        viewer.stepping = false;
+                        break;
+                    }
                 }
-                Sounds::SlimeEnemy => {
-                    audio::play(&resources.sounds_slimeenemy, 1.);
+                Err(e) => {
+                    console::error(&e.to_string());
+                    viewer.paused = true;
+                    viewer.stepping = false;
+                    break;
                 }
-                Sounds::Falling => {
-                    audio::play(&resources.sounds_falling, 2.);
+            }
+        }
+    }
+
+    if game.state == GameState::Running {
+        let (run, mut events) = game.world.tick();
+        if let Some(ghost) = &mut game.ghost
+            && let Err(e) = ghost.tick()
+        {
+            console::warn(&format!("Ghost stopped: {e}"));
+            game.ghost = None;
+        }
+        spawn_popups(game, &events);
+        // Versus runs share the table, their shots aren't any one player's
+        if !game.is_multiplayer() {
+            let unlocked = game.achievements.on_tick(&events);
+            unlock(game, unlocked);
+        }
+        if !run {
+            game.state = GameState::Playing;
+            events.extend(game.world.spawn_round());
+            if game.versus {
+                next_turn(game);
+            }
+            let round = game.world.round;
+            if !game.is_multiplayer() {
+                let unlocked = game.achievements.on_spawn_round(round);
+                unlock(game, unlocked);
+            }
+            let checksum = game.world.checksum();
+            if let Some(online) = &mut game.online {
+                send(online, &ClientMessage::Checksum { round, checksum });
+            }
+        }
+        if game.world.is_game_over() {
+            game.state = GameState::GameOver;
+            if !game.is_multiplayer() {
+                let score = Score {
+                    name: game.player_name.clone(),
+                    round: game.world.round,
+                    money: game.world.money,
+                    seed: game.replay.seed,
+                };
+                submit_score(game, score);
+                game.last_run = Some(game.replay.to_bytes());
+            }
+            if game.is_multiplayer() {
+                // Versus money is a score between the players, it doesn't go to the shop
+            } else if game.daily_run {
+                // Daily money stays out of the shop, the upgrades of the challenge aren't the
+                // player's
+                if let Some(daily) = &mut game.daily {
+                    daily.round = game.world.round;
+                    daily.money = game.world.money;
                 }
+            } else {
+                game.total_money += game.world.money;
+                game.world.money = 0;
+
+                game.best_round = game.best_round.max(game.world.round);
             }
+
+            save_progress(game);
         }
+        play_sounds(&events, resources);
     }
 }
 
@@ -92,20 +711,104 @@ enum GameState {
     Menu,
     Tuto1,
     Tuto2,
+    Replaying,
+    Achievements,
+    /// Waiting for the relay to start an online match
+    Lobby,
+}
+
+/// Text rising from where something was scored, in the frame of the table
+struct Popup {
+    text: String,
+    position: Vec2,
+    color: Vec4,
+    age: u32,
+}
+
+struct ReplayViewer {
+    playback: Playback,
+    paused: bool,
+    stepping: bool,
+    fast_forward: bool,
+}
+
+/// An online match, every client simulates the world and only the shots go through the relay
+struct Online {
+    connection: Connection,
+    /// The shots of this round were sent, the other players' are still missing
+    waiting: bool,
+    /// Shown to the player, empty while all is well
+    status: String,
+}
+
+/// Leaderboard shown on the game over screen, around the score just submitted
+struct LeaderboardView {
+    connection: Connection,
+    /// Sent once the connection opened
+    queued: Vec<LeaderboardRequest>,
+    top: Vec<Score>,
+    /// Rank of the player, starting at 1
+    rank: Option<usize>,
+    status: String,
 }
 
 struct Game {
     world: World,
     state: GameState,
-    moves: BTreeMap<usize, Vec2>,
-    selected: Option<usize>,
+    moves: BTreeMap<BallId, Vec2>,
+    selected: Option<BallId>,
     total_money: u64,
-    max_speed_level: usize,
-    start_mass_level: usize,
-    aim_assist_level: usize,
-    profitability_level: usize,
-    sliding_level: usize,
+    levels: UpgradeLevels,
     best_round: usize,
+    /// Id of the table the next run is played on
+    table: String,
+    replay: Replay,
+    viewer: Option<ReplayViewer>,
+    /// The last daily challenge attempted
+    daily: Option<DailyResult>,
+    /// The current run is the daily challenge
+    daily_run: bool,
+    /// The current run is a hot-seat game between two players
+    versus: bool,
+    /// Player planning the next shot, always the first one outside of versus runs and the local
+    /// player in online matches
+    turn: PlayerId,
+    online: Option<Online>,
+    /// Name of the player on the leaderboard
+    player_name: String,
+    leaderboard: Option<LeaderboardView>,
+    /// Previous run raced in the current one
+    ghost: Option<Ghost>,
+    /// Raced in the next run started from the shop
+    next_ghost: Option<Ghost>,
+    /// Replay of the last run played alone, as written by `Replay::to_bytes`
+    last_run: Option<Vec<u8>>,
+    achievements: AchievementTracker<'static>,
+    /// Achievements just unlocked, with the time they stop being shown at
+    toasts: Vec<(&'static Achievement, f64)>,
+    popups: Vec<Popup>,
+    /// How the last round was scored, shown until the next shot
+    round_score: Option<RoundScore>,
+    /// World, moves, turn and achievement progress from before the last shot, while it can still
+    /// be undone
+    last_shot: Option<(
+        Snapshot,
+        BTreeMap<BallId, Vec2>,
+        PlayerId,
+        AchievementProgress,
+    )>,
+    undos_left: u32,
+    /// Aimed ball and aim the preview was simulated for
+    aim_preview: Option<(BallId, Vec2, Preview)>,
+    /// Hint being searched, with the checksum of the world it's searched for
+    hint: Option<(u64, Search)>,
+}
+
+impl Game {
+    /// Several players share the table, the money is theirs and doesn't go to the shop
+    fn is_multiplayer(&self) -> bool {
+        self.versus || self.online.is_some()
+    }
 }
 
 fn draw_line(canvas: &mut Canvas2d, position: Vec2, length: Vec2, width: f32, color: Vec4) {
@@ -131,6 +834,37 @@ fn draw_line(canvas: &mut Canvas2d, position: Vec2, length: Vec2, width: f32, co
     canvas.set_view_matrix(previous);
 }
 
+/// Point the camera at the playing area of a table, scaled to fill the space the layout gives to
+/// the table
+fn table_camera(canvas: &mut Canvas2d, dimensions: Vec2, border: f32) {
+    let layout_size = LAYOUT_DIM + Vec2::splat(BORDER_SIZE * 2.);
+    let table_size = dimensions + Vec2::splat(border * 2.);
+    let scale = (layout_size / table_size).min_element();
+
+    canvas.camera_view_ratio(
+        table_size / 2. - Vec2::splat(border)
+            + Vec2::new(0., (layout_size.x / ASPECT_RATIO - layout_size.y) / 2.) / scale,
+        layout_size.x / 2. / scale,
+        ASPECT_RATIO,
+    );
+}
+
+/// Same frame as the playing area of the layout, for text drawn over the table
+fn layout_camera(canvas: &mut Canvas2d) {
+    table_camera(canvas, LAYOUT_DIM, BORDER_SIZE);
+}
+
+/// Frame of the whole layout, with the origin at the outer corner of the table border
+fn hud_camera(canvas: &mut Canvas2d) {
+    let layout_size = LAYOUT_DIM + Vec2::splat(BORDER_SIZE * 2.);
+
+    canvas.camera_view_ratio(
+        layout_size / 2. + Vec2::new(0., (layout_size.x / ASPECT_RATIO - layout_size.y) / 2.),
+        layout_size.x / 2.,
+        ASPECT_RATIO,
+    );
+}
+
 fn draw_ball(canvas: &mut Canvas2d, position: Vec2, radius: f32, texture: &TextureRect) {
     canvas.draw_rect(
         position - radius,
@@ -140,102 +874,247 @@ fn draw_ball(canvas: &mut Canvas2d, position: Vec2, radius: f32, texture: &Textu
     );
 }
 
-fn draw_game(canvas: &mut Canvas2d, game: &mut Game, resources: &mut Resources) {
-    let table_size: Vec2 = WORLD_DIM + Vec2::splat(BORDER_SIZE * 2.);
+fn draw_game(canvas: &mut Canvas2d, world: &World, resources: &mut Resources) {
+    let table = world.table();
+    table_camera(canvas, table.dimensions, table.border);
 
-    canvas.camera_view_ratio(
-        table_size / 2. - Vec2::splat(BORDER_SIZE)
-            + Vec2::new(0., (table_size.x / ASPECT_RATIO - table_size.y) / 2.),
-        table_size.x / 2.,
-        ASPECT_RATIO,
-    );
+    for item in &world.items {
+        draw_ball(
+            canvas,
+            item.position,
+            item.radius(),
+            &resources.items[item.kind.texture()],
+        );
+    }
 
-    for &coin in &game.world.coins {
-        draw_ball(canvas, coin, world::COIN_RADIUS, &resources.coin);
+    for obstacle in &world.obstacles {
+        match *obstacle {
+            Obstacle::Post { center, radius } => draw_ball(canvas, center, radius, &resources.post),
+            Obstacle::Bumper { center, radius, .. } => {
+                draw_ball(canvas, center, radius, &resources.bumper)
+            }
+            Obstacle::Wall { start, end } => draw_line(
+                canvas,
+                start,
+                start - end,
+                obstacles::WALL_THICKNESS * 2.,
+                color::rgb(0.35, 0.2, 0.1),
+            ),
+        }
     }
 
-    for ball in &game.world.balls {
+    for ball in &world.balls {
         let ball = ball.borrow();
 
         draw_ball(
             canvas,
             ball.position,
             ball.radius,
-            if let BallType::Enemy(e) = ball.letypedelaboule {
-                &resources.balls[e.timer]
-            } else {
-                &resources.slimeball
-            },
+            ball_texture(&ball, resources),
         );
     }
 }
 
-fn draw_aim(canvas: &mut Canvas2d, start_pos: Vec2, length: Vec2) {
-    let length = length * -1.;
+fn ball_texture<'a>(ball: &Ball, resources: &'a Resources) -> &'a TextureRect {
+    if let BallType::Enemy(e) = ball.letypedelaboule {
+        // Once broken, shielded enemies look like the basic ones
+        let archetype = if e.archetype == Archetype::Shielded && !e.shield {
+            Archetype::Basic
+        } else {
+            e.archetype
+        };
+        &resources.enemy_balls[&archetype][e.timer.min(5)]
+    } else if ball.letypedelaboule == BallType::Player(PlayerId(1)) {
+        &resources.slimeball2
+    } else {
+        &resources.slimeball
+    }
+}
 
-    let mut target = start_pos + length;
+fn draw_popups(canvas: &mut Canvas2d, popups: &[Popup], resources: &mut Resources) {
+    for popup in popups {
+        let fade = 1. - popup.age as f32 / POPUP_TICKS as f32;
+        canvas.draw_text(
+            popup.position + Vec2::Y * popup.age as f32 * POPUP_RISE,
+            0.03,
+            &popup.text,
+            &mut resources.font,
+            popup.color * Vec4::new(1., 1., 1., fade),
+            &canvas.white_texture(),
+        );
+    }
+}
 
-    if target.x < 0. {
-        let ratio: f32 = length.y / length.x;
-        let diff_x = 0. - target.x;
-        target.x = 0.;
-        target.y += diff_x * ratio;
-    } else if target.x > WORLD_DIM.x {
-        let ratio: f32 = length.y / length.x;
-        let diff_x = target.x - WORLD_DIM.x;
-        target.y -= diff_x * ratio;
-        target.x = WORLD_DIM.x
+fn power_up_name(kind: ItemKind) -> &'static str {
+    match kind {
+        ItemKind::Coin => "Coin",
+        ItemKind::Magnet => "Magnet",
+        ItemKind::Freeze => "Freeze",
+        ItemKind::DoubleCoin => "Double coin",
+        ItemKind::TripleSplit => "Triple split",
     }
+}
+
+/// Power-ups picked up by the player and not used yet, with their icon
+fn draw_power_ups(
+    canvas: &mut Canvas2d,
+    world: &World,
+    player: PlayerId,
+    resources: &mut Resources,
+) {
+    let power_ups = &world.power_ups[player.0 as usize];
+    let pending = [
+        (ItemKind::Freeze, u32::from(world.frozen)),
+        (ItemKind::DoubleCoin, power_ups.doubled_coins),
+        (ItemKind::TripleSplit, power_ups.triple_splits.len() as u32),
+    ];
+
+    let mut position = Vec2::new(0.05, 1.31);
+    for (kind, count) in pending {
+        if count == 0 {
+            continue;
+        }
 
-    if target.y < 0. {
-        let ratio: f32 = length.x / length.y;
-        let diff_y = 0. - target.y;
-        target.y = 0.;
-        target.x += diff_y * ratio;
-    } else if target.y > WORLD_DIM.y {
-        let ratio: f32 = length.x / length.y;
-        let diff_y = target.y - WORLD_DIM.y;
-        target.x -= diff_y * ratio;
-        target.y = WORLD_DIM.y
+        canvas.draw_rect(
+            position,
+            Vec2::splat(0.05),
+            color::WHITE,
+            &resources.items[kind.texture()],
+        );
+        canvas.draw_text(
+            position + Vec2::new(0.06, 0.01),
+            0.04,
+            &format!("{} x{count}", power_up_name(kind)),
+            &mut resources.font,
+            POWER_UP_COLOR,
+            &canvas.white_texture(),
+        );
+        position.x += 0.4;
     }
+}
+
+/// Multiplier of the round being played, then the breakdown of its score once it's over
+fn draw_round_score(canvas: &mut Canvas2d, game: &Game, resources: &mut Resources) {
+    let text = match (game.state, game.round_score) {
+        (GameState::Running, _) => {
+            let multiplier = game.world.round_score().multiplier;
+            if multiplier <= 1 {
+                return;
+            }
+            format!("Combo x{multiplier}")
+        }
+        (GameState::Playing, Some(score)) if score.multiplier > 1 => format!(
+            "Last round: {} coins x{} = +{} bonus   {} pockets, {} halves, {} bank shots",
+            score.coins,
+            score.multiplier,
+            score.bonus,
+            score.pockets,
+            score.collectors,
+            score.bank_shots
+        ),
+        _ => return,
+    };
 
-    draw_line(
-        canvas,
-        start_pos,
-        (target - start_pos) * -1.,
-        0.001,
-        color::rgb(1., 0., 0.),
+    canvas.draw_text(
+        Vec2::new(0.75, 1.12),
+        0.05,
+        &text,
+        &mut resources.font,
+        color::rgb(1., 1., 0.),
+        &canvas.white_texture(),
     );
 }
 
+/// Balls of the raced run, seen through over the live table
+fn draw_ghost(canvas: &mut Canvas2d, ghost: &Ghost, resources: &Resources) {
+    for ball in &ghost.world().balls {
+        let ball = ball.borrow();
+
+        canvas.draw_rect(
+            ball.position - ball.radius,
+            Vec2::splat(ball.radius * 2.),
+            GHOST_COLOR,
+            ball_texture(&ball, resources),
+        );
+    }
+}
+
+/// Draw the paths of the aimed halves in red and of the balls they hit in yellow
+fn draw_preview(canvas: &mut Canvas2d, preview: &Preview, halves: usize, resources: &Resources) {
+    for (index, path) in preview.paths.iter().enumerate() {
+        let color = if index < halves {
+            color::rgb(1., 0., 0.)
+        } else {
+            color::rgb(1., 1., 0.)
+        };
+        for segment in path.points.windows(2) {
+            draw_line(canvas, segment[0], segment[0] - segment[1], 0.002, color);
+        }
+        // Pocketed paths end on their pocket
+        if path.pocket.is_some()
+            && let Some(&pocket) = path.points.last()
+        {
+            draw_ball(canvas, pocket, 0.02, &resources.aimcircle);
+        }
+    }
+
+    if let Some((_, point)) = preview.first_hit {
+        draw_ball(canvas, point, 0.01, &resources.aimcircle);
+    }
+}
+
 fn render_tick(canvas: &mut Canvas2d, game: &mut Game, resources: &mut Resources) {
+    poll_online(game, resources);
+    poll_leaderboard(game);
+
     canvas.fit_screen();
 
     canvas.clear(color::rgb(0., 0., 0.));
 
-    let table_size: Vec2 = WORLD_DIM + Vec2::splat(BORDER_SIZE * 2.);
-
-    canvas.camera_view_ratio(
-        table_size / 2. + Vec2::new(0., (table_size.x / ASPECT_RATIO - table_size.y) / 2.),
-        table_size.x / 2.,
-        ASPECT_RATIO,
-    );
-
+    // The replay viewer shows the table of the replayed run
+    let table = match &game.viewer {
+        Some(viewer) => viewer.playback.world.table(),
+        None => game.world.table(),
+    };
+    table_camera(canvas, table.dimensions, table.border);
     canvas.draw_rect(
-        Vec2::new(0.05, 1.20),
-        Vec2::new(0.1, 0.1),
+        Vec2::splat(-table.border),
+        table.dimensions + Vec2::splat(table.border * 2.),
         color::WHITE,
-        &resources.coin_flat,
+        resources
+            .tables
+            .get(table.background.as_str())
+            .unwrap_or(&canvas.white_texture()),
     );
 
-    canvas.draw_text(
-        Vec2::new(0.175, 1.225),
-        0.1,
-        &format!("{}", game.world.money + game.total_money),
-        &mut resources.font,
-        color::WHITE,
-        &canvas.white_texture(),
-    );
+    hud_camera(canvas);
+
+    if game.is_multiplayer() {
+        draw_versus_hud(canvas, game, resources);
+    } else {
+        canvas.draw_rect(
+            Vec2::new(0.05, 1.20),
+            Vec2::new(0.1, 0.1),
+            color::WHITE,
+            &resources.coin_flat,
+        );
+
+        canvas.draw_text(
+            Vec2::new(0.175, 1.225),
+            0.1,
+            &format!(
+                "{}",
+                if game.daily_run {
+                    game.world.money
+                } else {
+                    game.world.money + game.total_money
+                }
+            ),
+            &mut resources.font,
+            color::WHITE,
+            &canvas.white_texture(),
+        );
+    }
 
     canvas.draw_text(
         Vec2::new(0.5, 1.225),
@@ -246,7 +1125,7 @@ fn render_tick(canvas: &mut Canvas2d, game: &mut Game, resources: &mut Resources
         &canvas.white_texture(),
     );
 
-    if game.best_round > 0 {
+    if game.best_round > 0 && !game.is_multiplayer() {
         canvas.draw_text(
             Vec2::new(1.5, 1.225),
             0.1,
@@ -257,97 +1136,118 @@ fn render_tick(canvas: &mut Canvas2d, game: &mut Game, resources: &mut Resources
         );
     }
 
-    canvas.draw_rect(
-        Vec2::new(0., 0.),
-        table_size,
-        color::WHITE,
-        &resources.pool_table,
-    );
+    if let Some(ghost) = &game.ghost
+        && !game.is_multiplayer()
+        && matches!(game.state, GameState::Playing | GameState::Running)
+    {
+        let ahead = ghost.money_ahead(game.world.money);
+        canvas.draw_text(
+            Vec2::new(0.05, 1.12),
+            0.06,
+            &format!("vs {}: {ahead:+} coins", ghost.name),
+            &mut resources.font,
+            if ahead >= 0 {
+                color::rgb(0.45, 1., 0.45)
+            } else {
+                color::rgb(1., 0.45, 0.45)
+            },
+            &canvas.white_texture(),
+        );
+    }
+
+    draw_round_score(canvas, game, resources);
+    draw_power_ups(canvas, &game.world, game.turn, resources);
 
     match game.state {
         GameState::Playing => {
-            draw_game(canvas, game, resources);
-            for (i, b) in game.world.balls.iter().enumerate() {
-                if let Some(&m) = game.moves.get(&i) {
-                    let b = b.borrow();
+            draw_game(canvas, &game.world, resources);
+            if let Some(ghost) = &game.ghost {
+                draw_ghost(canvas, ghost, resources);
+            }
+            draw_popups(canvas, &game.popups, resources);
+            for b in &game.world.balls {
+                let b = b.borrow();
+                if let Some(&m) = game.moves.get(&b.id) {
                     draw_line(canvas, b.position, m, 0.005, color::WHITE);
                     draw_line(canvas, b.position, m * -1., 0.005, color::WHITE);
                 }
             }
 
-            if input::is_button_pressed(Button::Left) {
+            // Sent shots are final, online players wait for the others with their aim shown
+            let waiting = game.online.as_ref().is_some_and(|online| online.waiting);
+
+            if input::is_button_pressed(Button::Left) && !waiting {
                 let mouse_pos = input::mouse_position().as_vec2();
 
-                for (i, b) in game.world.balls.iter().enumerate() {
+                for b in &game.world.balls {
                     let b = b.borrow();
 
-                    if let BallType::Player = b.letypedelaboule {
-                        if b.position.distance(canvas.screen_to_world_pos(mouse_pos)) < b.radius {
-                            game.selected = Some(i);
-                        }
+                    // Only the slimes of the player whose turn it is can be aimed
+                    if b.letypedelaboule == BallType::Player(game.turn)
+                        && b.position.distance(canvas.screen_to_world_pos(mouse_pos)) < b.radius
+                    {
+                        game.selected = Some(b.id);
                     }
                 }
             }
-            if let Some(selected) = game.selected {
-                let mut move_vector = canvas.screen_to_world_pos(input::mouse_position().as_vec2())
-                    - game.world.balls[selected].borrow().position;
+            if let Some(selected) = game.selected
+                && let Some(ball_pos) = game.world.ball(selected).map(|b| b.borrow().position)
+            {
+                let mut move_vector =
+                    canvas.screen_to_world_pos(input::mouse_position().as_vec2()) - ball_pos;
 
                 let pos_vector = move_vector;
 
-                if move_vector.length() > 0.15 {
-                    move_vector *= 0.15 / move_vector.length();
-                }
+                move_vector = move_vector.clamp_length_max(world::MAX_AIM);
 
                 if pos_vector.length() > 0.2 {
                     game.selected = None
                 }
 
+                // Letting go without dragging takes the shot of the slime back
                 if !input::is_button_down(Button::Left) {
-                    game.moves.insert(selected, move_vector);
+                    if move_vector == Vec2::ZERO {
+                        game.moves.remove(&selected);
+                    } else {
+                        game.moves.insert(selected, move_vector);
+                    }
                     game.selected = None;
                 }
 
-                let ball_pos = game.world.balls[selected].borrow().position;
-
-                draw_ball(canvas, ball_pos, 0.15, &resources.aimcircle);
+                draw_ball(canvas, ball_pos, world::MAX_AIM, &resources.aimcircle);
 
                 draw_line(canvas, ball_pos, move_vector, 0.01, color::WHITE);
                 draw_line(canvas, ball_pos, move_vector * -1., 0.01, color::WHITE);
 
-                if game.aim_assist_level == 1 {
-                    draw_aim(
-                        canvas,
-                        ball_pos,
-                        move_vector.normalize_or_zero() * AIM_ASSIST_LENGTH,
-                    );
-                }
-                if game.aim_assist_level == 2 {
-                    draw_aim(
-                        canvas,
-                        ball_pos,
-                        move_vector.normalize_or_zero() * AIM_ASSIST_LENGTH,
-                    );
-                    draw_aim(
-                        canvas,
-                        ball_pos,
-                        move_vector.normalize_or_zero() * AIM_ASSIST_LENGTH * -1.,
-                    );
-                }
-                if game.aim_assist_level == 3 {
-                    draw_aim(
-                        canvas,
-                        ball_pos,
-                        move_vector.normalize_or_zero() * WORLD_DIM.x,
-                    );
-                    draw_aim(
-                        canvas,
-                        ball_pos,
-                        move_vector.normalize_or_zero() * WORLD_DIM.x * -1.,
-                    );
+                // Daily challenges come with their own upgrades
+                let aim_assist = UpgradeRegistry::builtin()
+                    .stats(&game.replay.levels)
+                    .aim_assist;
+                if aim_assist.lines >= 1 && move_vector != Vec2::ZERO {
+                    // Only simulate again when the aim moved
+                    let stale = game
+                        .aim_preview
+                        .as_ref()
+                        .is_none_or(|(id, aim, _)| (*id, *aim) != (selected, move_vector));
+                    if stale {
+                        let mut moves = game.moves.clone();
+                        moves.insert(selected, move_vector);
+                        game.aim_preview =
+                            Preview::new(&game.world, game.turn, &moves, selected, aim_assist)
+                                .ok()
+                                .map(|preview| (selected, move_vector, preview));
+                    }
+                    if let Some((_, _, preview)) = &game.aim_preview {
+                        draw_preview(canvas, preview, aim_assist.lines as usize, resources);
+                    }
                 }
+            } else {
+                game.selected = None;
+                game.aim_preview = None;
             }
 
             if game.best_round == 0 && !game.moves.is_empty() && game.world.round == 0 {
+                layout_camera(canvas);
                 canvas.draw_text(
                     Vec2::new(0.3, 0.2),
                     0.2,
@@ -358,31 +1258,120 @@ fn render_tick(canvas: &mut Canvas2d, game: &mut Game, resources: &mut Resources
                 );
             }
 
-            if !game.moves.is_empty() && input::is_key_pressed(Key::Space) {
-                game.state = GameState::Running;
-                audio::play(&resources.sounds_shot, 1.);
+            let checksum = game.world.checksum();
+            if game
+                .hint
+                .as_ref()
+                .is_some_and(|(hinted, _)| *hinted != checksum)
+            {
+                game.hint = None;
+            }
+            if input::is_key_pressed(Key::H) && !waiting && game.hint.is_none() {
+                game.hint = Some((checksum, Solver::hint().search(&game.world, game.turn)));
+            }
+            if let Some((_, search)) = &mut game.hint
+                && let Poll::Ready(hint) = search.step(HINT_TICKS_PER_FRAME)
+            {
+                if let Some(hint) = hint {
+                    game.moves = hint.moves;
+                }
+                game.hint = None;
+            }
+
+            layout_camera(canvas);
+            canvas.draw_text(
+                Vec2::new(0.05, 0.05),
+                0.06,
+                if game.hint.is_some() {
+                    "Looking for a hint..."
+                } else {
+                    "PRESS H FOR a hint"
+                },
+                &mut resources.font,
+                TEXT_COLOR,
+                &canvas.white_texture(),
+            );
+
+            if game.last_shot.is_some() {
+                canvas.draw_text(
+                    Vec2::new(0.7, 0.05),
+                    0.06,
+                    &format!("PRESS U TO undo: {} left", game.undos_left),
+                    &mut resources.font,
+                    TEXT_COLOR,
+                    &canvas.white_texture(),
+                );
+            }
+
+            if input::is_key_pressed(Key::U) {
+                undo(game);
+            }
 
-                game.world.launch_round(&game.moves);
+            if game.online.is_some() {
+                let has_slime = game
+                    .world
+                    .balls
+                    .iter()
+                    .any(|ball| ball.borrow().letypedelaboule == BallType::Player(game.turn));
+                // Players without slimes still have to tell the relay they pass
+                if !waiting
+                    && (!has_slime || !game.moves.is_empty() && input::is_key_pressed(Key::Space))
+                {
+                    submit_moves(game);
+                }
+            } else if !game.moves.is_empty() && input::is_key_pressed(Key::Space) {
+                let snapshot = game.world.snapshot();
+                match game.world.launch_round(game.turn, &game.moves) {
+                    Ok(_) => {
+                        game.replay.record(game.world.round, game.turn, &game.moves);
+                        launch_ghost(game);
+                        game.round_score = None;
+                        if !game.is_multiplayer() {
+                            game.achievements.round_launched();
+                        }
+                        if game.undos_left > 0 {
+                            game.last_shot = Some((
+                                snapshot,
+                                game.moves.clone(),
+                                game.turn,
+                                game.achievements.progress.clone(),
+                            ));
+                        }
+                        game.state = GameState::Running;
+                        audio::play(&resources.sounds_shot, 1.);
+                    }
+                    Err(e) => console::warn(&format!("Shot discarded: {e}")),
+                }
 
                 game.moves.clear();
             }
         }
 
         GameState::Running => {
-            draw_game(canvas, game, resources);
+            draw_game(canvas, &game.world, resources);
+            if let Some(ghost) = &game.ghost {
+                draw_ghost(canvas, ghost, resources);
+            }
+            draw_popups(canvas, &game.popups, resources);
+
+            if input::is_key_pressed(Key::U) {
+                undo(game);
+            }
         }
         GameState::GameOver => {
-            draw_game(canvas, game, resources);
+            draw_game(canvas, &game.world, resources);
 
             canvas.draw_rect(
                 Vec2::ZERO,
-                WORLD_DIM,
+                game.world.table().dimensions,
                 color::rgba(0., 0., 0., 0.4),
                 &canvas.white_texture(),
             );
 
+            layout_camera(canvas);
+
             canvas.draw_text(
-                WORLD_DIM / 2. - Vec2::new(0.65, 0.),
+                LAYOUT_DIM / 2. - Vec2::new(0.65, 0.),
                 0.4,
                 "Game Over",
                 &mut resources.font,
@@ -390,8 +1379,25 @@ fn render_tick(canvas: &mut Canvas2d, game: &mut Game, resources: &mut Resources
                 &canvas.white_texture(),
             );
 
+            if game.versus {
+                let money = &game.world.player_money;
+                let (text, color) = match money[0].cmp(&money[1]) {
+                    std::cmp::Ordering::Greater => ("Player 1 wins", PLAYER_COLORS[0]),
+                    std::cmp::Ordering::Less => ("Player 2 wins", PLAYER_COLORS[1]),
+                    std::cmp::Ordering::Equal => ("Draw", TEXT_COLOR),
+                };
+                canvas.draw_text(
+                    Vec2::new(LAYOUT_DIM.x / 2. - 0.35, LAYOUT_DIM.y / 2. - 0.15),
+                    0.12,
+                    text,
+                    &mut resources.font,
+                    color,
+                    &canvas.white_texture(),
+                );
+            }
+
             canvas.draw_text(
-                Vec2::new(WORLD_DIM.x / 2. - 0.35, 0.35),
+                Vec2::new(LAYOUT_DIM.x / 2. - 0.35, 0.35),
                 0.1,
                 "PRESS SPACE TO continue",
                 &mut resources.font,
@@ -399,33 +1405,126 @@ fn render_tick(canvas: &mut Canvas2d, game: &mut Game, resources: &mut Resources
                 &canvas.white_texture(),
             );
 
+            canvas.draw_text(
+                Vec2::new(LAYOUT_DIM.x / 2. - 0.35, 0.22),
+                0.1,
+                "PRESS R TO watch the replay",
+                &mut resources.font,
+                TEXT_COLOR,
+                &canvas.white_texture(),
+            );
+
+            if let Some(view) = &game.leaderboard {
+                draw_leaderboard(canvas, view, &game.player_name, resources);
+
+                if !view.top.is_empty() {
+                    canvas.draw_text(
+                        Vec2::new(LAYOUT_DIM.x / 2. - 0.35, 0.09),
+                        0.1,
+                        "PRESS 1-5 TO race a listed run",
+                        &mut resources.font,
+                        TEXT_COLOR,
+                        &canvas.white_texture(),
+                    );
+                }
+            }
+
+            for (index, key) in LEADERBOARD_KEYS.into_iter().enumerate() {
+                if input::is_key_pressed(key) {
+                    request_ghost(game, index);
+                }
+            }
+
             if input::is_key_pressed(Key::Space) {
-                game.state = GameState::Shopping
+                close_leaderboard(game);
+                game.state = if game.daily_run || game.is_multiplayer() {
+                    GameState::Menu
+                } else {
+                    GameState::Shopping
+                };
+                // The match is over, the connection to the relay isn't needed anymore
+                if game.online.is_some() {
+                    new_run(game);
+                }
+            }
+
+            if input::is_key_pressed(Key::R) {
+                game.viewer = Some(ReplayViewer {
+                    playback: Playback::new(game.replay.clone()),
+                    paused: false,
+                    stepping: false,
+                    fast_forward: false,
+                });
+                game.state = GameState::Replaying;
+            }
+        }
+        GameState::Replaying => {
+            if let Some(viewer) = &mut game.viewer {
+                draw_game(canvas, &viewer.playback.world, resources);
+
+                if input::is_key_pressed(Key::P) {
+                    viewer.paused = !viewer.paused;
+                }
+                if input::is_key_pressed(Key::N) {
+                    viewer.paused = true;
+                    viewer.stepping = true;
+                }
+                if input::is_key_pressed(Key::F) {
+                    viewer.fast_forward = !viewer.fast_forward;
+                }
+
+                layout_camera(canvas);
+
+                let status = if viewer.playback.is_finished() {
+                    "Replay over"
+                } else if viewer.paused && !viewer.stepping {
+                    "Paused"
+                } else if viewer.fast_forward {
+                    "Fast forward"
+                } else {
+                    "Replay"
+                };
+
+                canvas.draw_text(
+                    Vec2::new(0.05, 0.05),
+                    0.08,
+                    &format!("{status} - round {}", viewer.playback.world.round),
+                    &mut resources.font,
+                    TEXT_COLOR,
+                    &canvas.white_texture(),
+                );
+
+                canvas.draw_text(
+                    Vec2::new(0.05, -0.05),
+                    0.05,
+                    "P pause   N next round   F fast forward   ESCAPE back",
+                    &mut resources.font,
+                    TEXT_COLOR,
+                    &canvas.white_texture(),
+                );
+            }
+
+            if input::is_key_pressed(Key::Escape) {
+                game.viewer = None;
+                game.state = GameState::GameOver;
             }
         }
         GameState::Shopping => {
-            canvas.camera_view_ratio(
-                table_size / 2. - Vec2::splat(BORDER_SIZE)
-                    + Vec2::new(0., (table_size.x / ASPECT_RATIO - table_size.y) / 2.),
-                table_size.x / 2.,
-                ASPECT_RATIO,
-            );
+            let table = game.world.table();
+            table_camera(canvas, table.dimensions, table.border);
 
             canvas.draw_rect(
                 Vec2::ZERO,
-                WORLD_DIM,
+                table.dimensions,
                 color::rgba(0., 0., 0., 0.4),
                 &canvas.white_texture(),
             );
 
-            canvas.camera_view_ratio(
-                table_size / 2. + Vec2::new(0., (table_size.x / ASPECT_RATIO - table_size.y) / 2.),
-                table_size.x / 2.,
-                ASPECT_RATIO,
-            );
+            hud_camera(canvas);
 
+            let layout_size = LAYOUT_DIM + Vec2::splat(BORDER_SIZE * 2.);
             let icon_middle_pos =
-                Vec2::new(table_size.x / 2. - ICON_SIZE.x / 2., table_size.y / 2.);
+                Vec2::new(layout_size.x / 2. - ICON_SIZE.x / 2., layout_size.y / 2.);
 
             let mouse_position = if input::is_button_pressed(Button::Left) {
                 Some(canvas.screen_to_world_pos(input::mouse_position().as_vec2()))
@@ -433,78 +1532,99 @@ fn render_tick(canvas: &mut Canvas2d, game: &mut Game, resources: &mut Resources
                 None
             };
 
-            // Icon
-            draw_upgrade(
-                canvas,
-                icon_middle_pos - Vec2::new(2. * ICON_SPACE.x, 0.),
-                &resources.aim_upgrade.clone(),
-                &PRICE_AIM_ASSIST,
-                &mut game.aim_assist_level,
-                &mut game.total_money,
-                resources,
-                &mouse_position,
-            );
+            let mut bought = false;
+
+            let registry = UpgradeRegistry::builtin();
+            let upgrade_count = registry.iter().count();
+
+            for (index, upgrade) in registry.iter().enumerate() {
+                let offset = index as f32 - (upgrade_count - 1) as f32 / 2.;
+                let mut level = game.levels.get(&upgrade.id);
+
+                if draw_upgrade(
+                    canvas,
+                    icon_middle_pos + Vec2::new(offset * ICON_SPACE.x, 0.),
+                    upgrade,
+                    &mut level,
+                    &mut game.total_money,
+                    resources,
+                    &mouse_position,
+                ) {
+                    game.levels.set(&upgrade.id, level);
+                    bought = true;
+                }
+            }
 
-            draw_upgrade(
-                canvas,
-                icon_middle_pos - Vec2::new(1. * ICON_SPACE.x, 0.),
-                &resources.speed_upgrade.clone(),
-                &PRICE_MAX_SPEED,
-                &mut game.max_speed_level,
-                &mut game.total_money,
-                resources,
-                &mouse_position,
-            );
+            if bought {
+                let unlocked = game.achievements.on_purchase(&game.levels);
+                unlock(game, unlocked);
+                save_progress(game);
+            }
 
-            draw_upgrade(
-                canvas,
-                icon_middle_pos,
-                &resources.coin_upgrade.clone(),
-                &PRICE_PROFITABILITY,
-                &mut game.profitability_level,
-                &mut game.total_money,
-                resources,
-                &mouse_position,
+            canvas.draw_text(
+                Vec2::new(0.65, 0.3),
+                0.1,
+                "PRESS SPACE TO retry",
+                &mut resources.font,
+                TEXT_COLOR,
+                &canvas.white_texture(),
             );
 
-            draw_upgrade(
-                canvas,
-                icon_middle_pos + Vec2::new(1. * ICON_SPACE.x, 0.),
-                &resources.heavy_upgrade.clone(),
-                &PRICE_START_MASS,
-                &mut game.start_mass_level,
-                &mut game.total_money,
-                resources,
-                &mouse_position,
-            );
+            let tables = TableRegistry::builtin();
+            let table_name = tables
+                .get(&game.table)
+                .unwrap_or_else(|| tables.default_table())
+                .name
+                .clone();
 
-            draw_upgrade(
-                canvas,
-                icon_middle_pos + Vec2::new(2. * ICON_SPACE.x, 0.),
-                &resources.slide_upgrade.clone(),
-                &PRICE_SLIDING,
-                &mut game.sliding_level,
-                &mut game.total_money,
-                resources,
-                &mouse_position,
+            canvas.draw_text(
+                Vec2::new(0.65, 0.18),
+                0.07,
+                &format!("PRESS T TO change table: {table_name}"),
+                &mut resources.font,
+                TEXT_COLOR,
+                &canvas.white_texture(),
             );
 
+            let ghost_text = match &game.next_ghost {
+                Some(ghost) => format!("Racing {}, PRESS G TO race alone", ghost.name),
+                None if game.last_run.is_some() => "PRESS G TO race your last run".to_owned(),
+                None => String::new(),
+            };
             canvas.draw_text(
-                Vec2::new(0.65, 0.3),
-                0.1,
-                "PRESS SPACE TO retry",
+                Vec2::new(0.65, 0.08),
+                0.07,
+                &ghost_text,
                 &mut resources.font,
                 TEXT_COLOR,
                 &canvas.white_texture(),
             );
 
+            if input::is_key_pressed(Key::G) {
+                game.next_ghost = match (&game.next_ghost, &game.last_run) {
+                    (None, Some(bytes)) => match Replay::from_bytes(bytes) {
+                        Ok(replay) => Some(Ghost::new("your last run", replay)),
+                        Err(e) => {
+                            console::warn(&format!("Can't race the last run: {e}"));
+                            None
+                        }
+                    },
+                    _ => None,
+                };
+                audio::play(&resources.sounds_button, 3.);
+            }
+
+            if input::is_key_pressed(Key::T) {
+                let next = tables
+                    .iter()
+                    .position(|table| table.id == game.table)
+                    .map_or(0, |index| (index + 1) % tables.iter().count());
+                game.table = tables.iter().nth(next).unwrap().id.clone();
+                audio::play(&resources.sounds_button, 3.);
+            }
+
             if input::is_key_pressed(Key::Space) {
-                game.world = World::new(
-                    game.max_speed_level,
-                    game.profitability_level,
-                    game.start_mass_level,
-                    game.sliding_level,
-                );
+                new_run(game);
                 game.state = GameState::Playing
             }
         }
@@ -518,10 +1638,112 @@ fn render_tick(canvas: &mut Canvas2d, game: &mut Game, resources: &mut Resources
                 &resources.title_screen,
             );
 
+            let today = Date::from_unix_millis(time::unix_millis());
+            let daily_text = match game.daily {
+                Some(daily) if daily.date == today => format!(
+                    "Daily challenge {today}: round {}, {} coins",
+                    daily.round, daily.money
+                ),
+                _ => "PRESS D FOR the daily challenge".to_owned(),
+            };
+            canvas.draw_text(
+                Vec2::new(-0.45, -0.55),
+                0.06,
+                &daily_text,
+                &mut resources.font,
+                TEXT_COLOR,
+                &canvas.white_texture(),
+            );
+            canvas.draw_text(
+                Vec2::new(-0.45, -0.63),
+                0.06,
+                "PRESS V FOR two players",
+                &mut resources.font,
+                TEXT_COLOR,
+                &canvas.white_texture(),
+            );
+            canvas.draw_text(
+                Vec2::new(-0.45, -0.71),
+                0.06,
+                "PRESS O TO play online",
+                &mut resources.font,
+                TEXT_COLOR,
+                &canvas.white_texture(),
+            );
+            canvas.draw_text(
+                Vec2::new(-0.45, -0.79),
+                0.06,
+                "PRESS A FOR achievements",
+                &mut resources.font,
+                TEXT_COLOR,
+                &canvas.white_texture(),
+            );
+
             if input::is_key_pressed(Key::Space) {
+                if game.daily_run || game.is_multiplayer() {
+                    new_run(game);
+                }
                 game.state = GameState::Tuto1;
                 audio::play(&resources.sounds_button, 3.);
             }
+
+            if input::is_key_pressed(Key::D) && start_daily(game) {
+                game.state = GameState::Playing;
+                audio::play(&resources.sounds_button, 3.);
+            }
+
+            if input::is_key_pressed(Key::V) {
+                start_versus(game);
+                game.state = GameState::Playing;
+                audio::play(&resources.sounds_button, 3.);
+            }
+
+            if input::is_key_pressed(Key::O) {
+                start_online(game);
+                audio::play(&resources.sounds_button, 3.);
+            }
+
+            if input::is_key_pressed(Key::A) {
+                game.state = GameState::Achievements;
+                audio::play(&resources.sounds_button, 3.);
+            }
+        }
+        GameState::Achievements => {
+            layout_camera(canvas);
+            draw_achievements(canvas, game, resources);
+
+            if input::is_key_pressed(Key::Escape) {
+                game.state = GameState::Menu;
+                audio::play(&resources.sounds_button, 3.);
+            }
+        }
+        GameState::Lobby => {
+            canvas.camera_view_ratio(Vec2::ZERO, 1., ASPECT_RATIO);
+
+            if let Some(online) = &game.online {
+                canvas.draw_text(
+                    Vec2::new(-0.45, 0.),
+                    0.08,
+                    &online.status,
+                    &mut resources.font,
+                    TEXT_COLOR,
+                    &canvas.white_texture(),
+                );
+            }
+            canvas.draw_text(
+                Vec2::new(-0.45, -0.15),
+                0.06,
+                "PRESS ESCAPE TO leave",
+                &mut resources.font,
+                TEXT_COLOR,
+                &canvas.white_texture(),
+            );
+
+            if input::is_key_pressed(Key::Escape) {
+                leave_online(game);
+                game.state = GameState::Menu;
+                audio::play(&resources.sounds_button, 3.);
+            }
         }
         GameState::Tuto1 => {
             canvas.camera_view_ratio(Vec2::ZERO, 1., ASPECT_RATIO);
@@ -560,6 +1782,32 @@ fn render_tick(canvas: &mut Canvas2d, game: &mut Game, resources: &mut Resources
         }
     }
 
+    if let Some(online) = &game.online
+        && game.state != GameState::Lobby
+    {
+        let status = if online.waiting && online.status.is_empty() {
+            "Waiting for the other player"
+        } else {
+            &online.status
+        };
+        layout_camera(canvas);
+        canvas.draw_text(
+            Vec2::new(0.05, LAYOUT_DIM.y - 0.1),
+            0.06,
+            status,
+            &mut resources.font,
+            TEXT_COLOR,
+            &canvas.white_texture(),
+        );
+    }
+
+    let now = time::unix_millis();
+    game.toasts.retain(|&(_, until)| until > now);
+    if !game.toasts.is_empty() {
+        layout_camera(canvas);
+        draw_toasts(canvas, &game.toasts, resources);
+    }
+
     canvas.camera_view_ratio(Vec2::ZERO, 1., ASPECT_RATIO);
 
     if input::is_button_down(Button::Left) {
@@ -582,16 +1830,187 @@ fn render_tick(canvas: &mut Canvas2d, game: &mut Game, resources: &mut Resources
     canvas.flush();
 }
 
+/// Every achievement with the progress made towards it, in the frame of the layout
+fn draw_achievements(canvas: &mut Canvas2d, game: &Game, resources: &mut Resources) {
+    let progress = &game.achievements.progress;
+    let mut position = Vec2::new(0.1, LAYOUT_DIM.y - 0.1);
+
+    canvas.draw_text(
+        position,
+        0.1,
+        "Achievements",
+        &mut resources.font,
+        TEXT_COLOR,
+        &canvas.white_texture(),
+    );
+    position.y -= 0.15;
+
+    for achievement in AchievementRegistry::builtin().iter() {
+        let (state, color) = if progress.is_unlocked(&achievement.id) {
+            ("unlocked".to_owned(), color::rgb(1., 1., 0.))
+        } else {
+            (
+                format!(
+                    "{}/{}",
+                    progress.progress(achievement),
+                    achievement.goal.target()
+                ),
+                TEXT_COLOR,
+            )
+        };
+        canvas.draw_text(
+            position,
+            0.05,
+            &format!(
+                "{}: {}   {state}",
+                achievement.name, achievement.description
+            ),
+            &mut resources.font,
+            color,
+            &canvas.white_texture(),
+        );
+        position.y -= 0.08;
+    }
+
+    canvas.draw_text(
+        Vec2::new(0.1, 0.05),
+        0.05,
+        "PRESS ESCAPE TO go back",
+        &mut resources.font,
+        TEXT_COLOR,
+        &canvas.white_texture(),
+    );
+}
+
+/// Achievements just unlocked, stacked in the top right corner of the layout
+fn draw_toasts(canvas: &mut Canvas2d, toasts: &[(&Achievement, f64)], resources: &mut Resources) {
+    let size = Vec2::new(0.7, 0.14);
+    let mut position = Vec2::new(LAYOUT_DIM.x - size.x - 0.05, LAYOUT_DIM.y - size.y - 0.05);
+
+    for (achievement, _) in toasts {
+        canvas.draw_rect(
+            position,
+            size,
+            color::rgba(0., 0., 0., 0.7),
+            &canvas.white_texture(),
+        );
+        canvas.draw_text(
+            position + Vec2::new(0.02, 0.08),
+            0.045,
+            &format!("Achievement unlocked: {}", achievement.name),
+            &mut resources.font,
+            color::rgb(1., 1., 0.),
+            &canvas.white_texture(),
+        );
+        canvas.draw_text(
+            position + Vec2::new(0.02, 0.025),
+            0.035,
+            &achievement.description,
+            &mut resources.font,
+            TEXT_COLOR,
+            &canvas.white_texture(),
+        );
+        position.y -= size.y + 0.03;
+    }
+}
+
+/// Best scores and the rank of the player, in the frame of the layout
+fn draw_leaderboard(
+    canvas: &mut Canvas2d,
+    view: &LeaderboardView,
+    player_name: &str,
+    resources: &mut Resources,
+) {
+    let mut position = Vec2::new(0.05, LAYOUT_DIM.y - 0.1);
+    let mut line = |canvas: &mut Canvas2d, text: &str, color: Vec4| {
+        canvas.draw_text(
+            position,
+            0.05,
+            text,
+            &mut resources.font,
+            color,
+            &canvas.white_texture(),
+        );
+        position.y -= 0.07;
+    };
+
+    let title = if view.status.is_empty() {
+        "Leaderboard"
+    } else {
+        &view.status
+    };
+    line(canvas, title, TEXT_COLOR);
+
+    for (index, score) in view.top.iter().enumerate() {
+        let color = if score.name == player_name {
+            color::rgb(1., 1., 0.)
+        } else {
+            TEXT_COLOR
+        };
+        line(
+            canvas,
+            &format!(
+                "{}. {}   round {}   {} coins",
+                index + 1,
+                score.name,
+                score.round,
+                score.money
+            ),
+            color,
+        );
+    }
+
+    if let Some(rank) = view.rank
+        && rank > view.top.len()
+    {
+        line(
+            canvas,
+            &format!("{player_name}: rank {rank}"),
+            color::rgb(1., 1., 0.),
+        );
+    }
+}
+
+/// Money of each player, the one whose turn it is is marked
+fn draw_versus_hud(canvas: &mut Canvas2d, game: &Game, resources: &mut Resources) {
+    for (player, &money) in game.world.player_money.iter().enumerate() {
+        let position = Vec2::new(0.05 + player as f32 * 1.45, 1.20);
+        let color = PLAYER_COLORS[player % PLAYER_COLORS.len()];
+
+        canvas.draw_rect(position, Vec2::new(0.1, 0.1), color, &resources.coin_flat);
+
+        let turn = if game.turn.0 as usize == player && game.state == GameState::Playing {
+            ">"
+        } else {
+            ""
+        };
+        canvas.draw_text(
+            position + Vec2::new(0.125, 0.025),
+            0.1,
+            &format!("P{} {money}{turn}", player + 1),
+            &mut resources.font,
+            color,
+            &canvas.white_texture(),
+        );
+    }
+}
+
 fn draw_upgrade(
     canvas: &mut Canvas2d,
     position: Vec2,
-    icon_texture: &TextureRect,
-    price: &[u64],
+    upgrade: &Upgrade,
     level: &mut usize,
     total_money: &mut u64,
     resources: &mut Resources,
     mouse_position: &Option<Vec2>,
-) {
+) -> bool {
+    let price = &upgrade.prices;
+    let icon_texture = resources
+        .upgrade_icons
+        .get(upgrade.icon.as_str())
+        .cloned()
+        .unwrap_or_else(|| canvas.white_texture());
+
     let color = if *level == price.len() {
         color::rgb(1., 1., 1.)
     } else if price[*level] <= *total_money {
@@ -606,7 +2025,7 @@ fn draw_upgrade(
         "Max !".to_owned()
     };
 
-    canvas.draw_rect(position, ICON_SIZE, color::WHITE, icon_texture);
+    canvas.draw_rect(position, ICON_SIZE, color::WHITE, &icon_texture);
 
     let button_position = position + Vec2::new(0., -BUTTON_SPACE);
 
@@ -639,19 +2058,21 @@ fn draw_upgrade(
         );
     }
 
-    if let Some(mouse_position) = mouse_position {
-        if mouse_position.x > button_position.x
-            && mouse_position.x < button_position.x + BUTTON_SIZE.x
-            && mouse_position.y > button_position.y
-            && mouse_position.y < button_position.y + BUTTON_SIZE.y
-            && *level < price.len()
-            && *total_money >= price[*level]
-        {
-            *total_money -= price[*level];
-            *level += 1;
-            audio::play(&resources.sounds_button, 3.);
-        }
+    if let Some(mouse_position) = mouse_position
+        && mouse_position.x > button_position.x
+        && mouse_position.x < button_position.x + BUTTON_SIZE.x
+        && mouse_position.y > button_position.y
+        && mouse_position.y < button_position.y + BUTTON_SIZE.y
+        && *level < price.len()
+        && *total_money >= price[*level]
+    {
+        *total_money -= price[*level];
+        *level += 1;
+        audio::play(&resources.sounds_button, 3.);
+        return true;
     }
+
+    false
 }
 
 async fn async_main() {
@@ -667,20 +2088,49 @@ async fn async_main() {
 
     audio::play_loop(&resources.main_theme, 0.3);
 
+    let save = load_progress();
+
     let mut game = Game {
         moves: BTreeMap::new(),
-        world: World::new(0, 0, 0, 0),
+        world: World::new(
+            0,
+            &UpgradeLevels::default(),
+            TableRegistry::builtin().default_table(),
+        ),
         state: GameState::Menu,
         selected: None,
-        aim_assist_level: 0,
-        max_speed_level: 0,
-        profitability_level: 0,
-        start_mass_level: 0,
-        sliding_level: 0,
-        total_money: 0,
-        best_round: 0,
+        levels: save.levels,
+        total_money: save.total_money,
+        best_round: save.best_round,
+        replay: Replay::new(
+            0,
+            UpgradeLevels::default(),
+            TableRegistry::builtin().default_table(),
+        ),
+        table: TableRegistry::builtin().default_table().id.clone(),
+        viewer: None,
+        daily: save.daily,
+        daily_run: false,
+        versus: false,
+        turn: PlayerId(0),
+        online: None,
+        player_name: load_player_name(),
+        leaderboard: None,
+        ghost: None,
+        next_ghost: None,
+        last_run: save.last_run,
+        achievements: AchievementTracker::new(AchievementRegistry::builtin(), save.achievements),
+        toasts: Vec::new(),
+        popups: Vec::new(),
+        round_score: None,
+        last_shot: None,
+        undos_left: 0,
+        aim_preview: None,
+        hint: None,
     };
 
+    new_run(&mut game);
+
     let mut tick_scheduler = TickScheduler::new(Duration::from_millis(1));
     draw_scheduler::set_on_draw(move || {
         for _ in 0..tick_scheduler.tick_count() {
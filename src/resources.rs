@@ -1,22 +1,28 @@
+use std::collections::BTreeMap;
+
 use marmalade::{
     audio::{self, Audio},
     font::{self, Font},
     image,
     render::canvas2d::{Canvas2d, TextureRect},
 };
+use upsi5_core::entity::Archetype;
 
 pub struct Resources {
-    pub pool_table: TextureRect,
+    /// Table backgrounds, by the name used in the table catalogue
+    pub tables: BTreeMap<&'static str, TextureRect>,
     pub slimeball: TextureRect,
-    pub balls: [TextureRect; 6],
+    pub slimeball2: TextureRect,
+    /// Enemy textures by archetype, indexed by the enemy timer
+    pub enemy_balls: BTreeMap<Archetype, [TextureRect; 6]>,
     pub aimcircle: TextureRect,
-    pub coin: TextureRect,
+    /// Coin and power-up textures, by the name given by the item kind
+    pub items: BTreeMap<&'static str, TextureRect>,
     pub coin_flat: TextureRect,
-    pub speed_upgrade: TextureRect,
-    pub slide_upgrade: TextureRect,
-    pub heavy_upgrade: TextureRect,
-    pub coin_upgrade: TextureRect,
-    pub aim_upgrade: TextureRect,
+    pub post: TextureRect,
+    pub bumper: TextureRect,
+    /// Shop icons, by the name used in the upgrade catalogue
+    pub upgrade_icons: BTreeMap<&'static str, TextureRect>,
     pub button: TextureRect,
     pub title_screen: TextureRect,
     pub tuto_1: TextureRect,
@@ -39,20 +45,135 @@ async fn load_texture(canvas: &mut Canvas2d, bytes: &[u8]) -> TextureRect {
     canvas.create_texture(&image::from_bytes(bytes).await)
 }
 
+async fn load_ball_set(canvas: &mut Canvas2d, bytes: [&[u8]; 6]) -> [TextureRect; 6] {
+    let [endball, ball1, ball2, ball3, ball4, ball5] = bytes;
+
+    [
+        load_texture(canvas, endball).await,
+        load_texture(canvas, ball1).await,
+        load_texture(canvas, ball2).await,
+        load_texture(canvas, ball3).await,
+        load_texture(canvas, ball4).await,
+        load_texture(canvas, ball5).await,
+    ]
+}
+
 impl Resources {
     pub async fn load(canvas: &mut Canvas2d) -> Self {
         let pool_table = load_texture(canvas, include_bytes!("../assets/pool_table.png")).await;
+        let pool_table_small =
+            load_texture(canvas, include_bytes!("../assets/pool_table_small.png")).await;
+        let pool_table_long =
+            load_texture(canvas, include_bytes!("../assets/pool_table_long.png")).await;
+        let pool_table_six_pocket = load_texture(
+            canvas,
+            include_bytes!("../assets/pool_table_six_pocket.png"),
+        )
+        .await;
         let slimeball = load_texture(canvas, include_bytes!("../assets/slimeball.png")).await;
+        let slimeball2 = load_texture(canvas, include_bytes!("../assets/slimeball2.png")).await;
 
-        let endball = load_texture(canvas, include_bytes!("../assets/endball.png")).await;
-        let ball1 = load_texture(canvas, include_bytes!("../assets/ball1.png")).await;
-        let ball2 = load_texture(canvas, include_bytes!("../assets/ball2.png")).await;
-        let ball3 = load_texture(canvas, include_bytes!("../assets/ball3.png")).await;
-        let ball4 = load_texture(canvas, include_bytes!("../assets/ball4.png")).await;
-        let ball5 = load_texture(canvas, include_bytes!("../assets/ball5.png")).await;
+        let enemy_balls = BTreeMap::from([
+            (
+                Archetype::Basic,
+                load_ball_set(
+                    canvas,
+                    [
+                        include_bytes!("../assets/endball.png"),
+                        include_bytes!("../assets/ball1.png"),
+                        include_bytes!("../assets/ball2.png"),
+                        include_bytes!("../assets/ball3.png"),
+                        include_bytes!("../assets/ball4.png"),
+                        include_bytes!("../assets/ball5.png"),
+                    ],
+                )
+                .await,
+            ),
+            (
+                Archetype::Anchor,
+                load_ball_set(
+                    canvas,
+                    [
+                        include_bytes!("../assets/anchorendball.png"),
+                        include_bytes!("../assets/anchorball1.png"),
+                        include_bytes!("../assets/anchorball2.png"),
+                        include_bytes!("../assets/anchorball3.png"),
+                        include_bytes!("../assets/anchorball4.png"),
+                        include_bytes!("../assets/anchorball5.png"),
+                    ],
+                )
+                .await,
+            ),
+            (
+                Archetype::Splitter,
+                load_ball_set(
+                    canvas,
+                    [
+                        include_bytes!("../assets/splitterendball.png"),
+                        include_bytes!("../assets/splitterball1.png"),
+                        include_bytes!("../assets/splitterball2.png"),
+                        include_bytes!("../assets/splitterball3.png"),
+                        include_bytes!("../assets/splitterball4.png"),
+                        include_bytes!("../assets/splitterball5.png"),
+                    ],
+                )
+                .await,
+            ),
+            (
+                Archetype::Bomb,
+                load_ball_set(
+                    canvas,
+                    [
+                        include_bytes!("../assets/bombendball.png"),
+                        include_bytes!("../assets/bombball1.png"),
+                        include_bytes!("../assets/bombball2.png"),
+                        include_bytes!("../assets/bombball3.png"),
+                        include_bytes!("../assets/bombball4.png"),
+                        include_bytes!("../assets/bombball5.png"),
+                    ],
+                )
+                .await,
+            ),
+            (
+                Archetype::Shielded,
+                load_ball_set(
+                    canvas,
+                    [
+                        include_bytes!("../assets/shieldedendball.png"),
+                        include_bytes!("../assets/shieldedball1.png"),
+                        include_bytes!("../assets/shieldedball2.png"),
+                        include_bytes!("../assets/shieldedball3.png"),
+                        include_bytes!("../assets/shieldedball4.png"),
+                        include_bytes!("../assets/shieldedball5.png"),
+                    ],
+                )
+                .await,
+            ),
+            (
+                Archetype::Ghost,
+                load_ball_set(
+                    canvas,
+                    [
+                        include_bytes!("../assets/ghostendball.png"),
+                        include_bytes!("../assets/ghostball1.png"),
+                        include_bytes!("../assets/ghostball2.png"),
+                        include_bytes!("../assets/ghostball3.png"),
+                        include_bytes!("../assets/ghostball4.png"),
+                        include_bytes!("../assets/ghostball5.png"),
+                    ],
+                )
+                .await,
+            ),
+        ]);
         let aimcircle = load_texture(canvas, include_bytes!("../assets/aimcircle.png")).await;
-        let coin: TextureRect = load_texture(canvas, include_bytes!("../assets/coin.png")).await;
+        let coin = load_texture(canvas, include_bytes!("../assets/coin.png")).await;
+        let magnet = load_texture(canvas, include_bytes!("../assets/magnet.png")).await;
+        let freeze = load_texture(canvas, include_bytes!("../assets/freeze.png")).await;
+        let double_coin = load_texture(canvas, include_bytes!("../assets/doublecoin.png")).await;
+        let triple_split = load_texture(canvas, include_bytes!("../assets/triplesplit.png")).await;
         let coin_flat = load_texture(canvas, include_bytes!("../assets/coinflat.png")).await;
+        let post = load_texture(canvas, include_bytes!("../assets/post.png")).await;
+        let bumper = load_texture(canvas, include_bytes!("../assets/bumper.png")).await;
         let speed_upgrade =
             load_texture(canvas, include_bytes!("../assets/speedupgrade.png")).await;
         let slide_upgrade =
@@ -61,6 +182,7 @@ impl Resources {
             load_texture(canvas, include_bytes!("../assets/heavyupgrade.png")).await;
         let coin_upgrade = load_texture(canvas, include_bytes!("../assets/coinupgrade.png")).await;
         let aim_upgrade = load_texture(canvas, include_bytes!("../assets/aimupgrade.png")).await;
+        let undo_upgrade = load_texture(canvas, include_bytes!("../assets/undoupgrade.png")).await;
         let button = load_texture(canvas, include_bytes!("../assets/button.png")).await;
         let title_screen = load_texture(canvas, include_bytes!("../assets/titlescreen.png")).await;
         let tuto_1 = load_texture(canvas, include_bytes!("../assets/tutomecanics1.png")).await;
@@ -86,21 +208,38 @@ impl Resources {
         let main_theme = audio::from_bytes(include_bytes!("../sounds/ludwig.flac")).await;
 
         Self {
-            pool_table,
+            tables: BTreeMap::from([
+                ("pool_table", pool_table),
+                ("pool_table_small", pool_table_small),
+                ("pool_table_long", pool_table_long),
+                ("pool_table_six_pocket", pool_table_six_pocket),
+            ]),
             slimeball,
-            balls: [endball, ball1, ball2, ball3, ball4, ball5],
+            slimeball2,
+            enemy_balls,
             aimcircle,
-            coin,
+            items: BTreeMap::from([
+                ("coin", coin),
+                ("doublecoin", double_coin),
+                ("freeze", freeze),
+                ("magnet", magnet),
+                ("triplesplit", triple_split),
+            ]),
             coin_flat,
+            post,
+            bumper,
             font,
             sounds_coin,
             sounds_shot,
             sounds_slimeslime,
-            aim_upgrade,
-            coin_upgrade,
-            heavy_upgrade,
-            slide_upgrade,
-            speed_upgrade,
+            upgrade_icons: BTreeMap::from([
+                ("aimupgrade", aim_upgrade),
+                ("coinupgrade", coin_upgrade),
+                ("heavyupgrade", heavy_upgrade),
+                ("slideupgrade", slide_upgrade),
+                ("speedupgrade", speed_upgrade),
+                ("undoupgrade", undo_upgrade),
+            ]),
             button,
             hand_open,
             hand_closed,
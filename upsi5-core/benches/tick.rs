@@ -0,0 +1,63 @@
+use glam::Vec2;
+use std::{cell::RefCell, hint::black_box, time::Instant};
+use upsi5_core::{
+    entity::{Archetype, Ball, BallId, BallType, EnemyData},
+    rng::Rng,
+    table::TableRegistry,
+    upgrades::UpgradeLevels,
+    world::World,
+};
+
+const TICKS: u32 = 200;
+const RADIUS: f32 = 0.008;
+
+fn crowded_world(count: usize) -> World {
+    let mut rng = Rng::new(count as u64);
+    let mut world = World::new(
+        0,
+        &UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+    );
+    let dimensions = world.table().dimensions;
+
+    world.balls = (0..count)
+        .map(|index| {
+            let position = Vec2::new(
+                rng.rand_range(0.1, dimensions.x as f64 - 0.1) as f32,
+                rng.rand_range(0.1, dimensions.y as f64 - 0.1) as f32,
+            );
+            let speed = Vec2::new(
+                rng.rand_range(-0.002, 0.002) as f32,
+                rng.rand_range(-0.002, 0.002) as f32,
+            );
+
+            RefCell::new(Ball::new(
+                BallId(index as u64),
+                0.05,
+                position,
+                speed,
+                1.,
+                RADIUS,
+                BallType::Enemy(EnemyData::new(Archetype::Basic, 5)),
+            ))
+        })
+        .collect();
+
+    world
+}
+
+fn main() {
+    println!("{:>6} {:>14}", "balls", "time per tick");
+
+    for count in [10, 50, 100, 250, 500, 1000, 2000] {
+        let mut world = crowded_world(count);
+
+        let start = Instant::now();
+        for _ in 0..TICKS {
+            black_box(world.tick());
+        }
+        let elapsed = start.elapsed() / TICKS;
+
+        println!("{count:>6} {elapsed:>14.2?}");
+    }
+}
@@ -0,0 +1,271 @@
+//! Goals unlocked by playing. The tracker only looks at the events of the world, the rounds it
+//! reaches and the upgrades bought, the simulation knows nothing about achievements.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    sync::OnceLock,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::entity::BallType;
+use crate::events::Event;
+use crate::upgrades::{UpgradeLevels, UpgradeRegistry};
+
+const BUILTIN_ACHIEVEMENTS: &str = include_str!("../data/achievements.json");
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Goal {
+    /// Enemies pocketed by the shots of a single round
+    PocketedInOneShot {
+        count: u64,
+    },
+    /// Slime merges during a single round
+    MergedInOneRound {
+        count: u64,
+    },
+    Round {
+        round: usize,
+    },
+    /// Round reached in a run played without any level of the upgrade
+    RoundWithout {
+        round: usize,
+        upgrade: String,
+    },
+    /// Enemies pocketed over all runs
+    PocketedTotal {
+        count: u64,
+    },
+    /// Coins picked up over all runs, whatever they were worth
+    CoinsTotal {
+        count: u64,
+    },
+    /// Any upgrade bought up to its last level
+    MaxedUpgrade,
+}
+
+impl Goal {
+    /// Progress needed to unlock the achievement
+    pub fn target(&self) -> u64 {
+        match self {
+            Self::PocketedInOneShot { count }
+            | Self::MergedInOneRound { count }
+            | Self::PocketedTotal { count }
+            | Self::CoinsTotal { count } => *count,
+            Self::Round { round } | Self::RoundWithout { round, .. } => *round as u64,
+            Self::MaxedUpgrade => 1,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct Achievement {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub goal: Goal,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RegistryError(pub String);
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid achievement registry: {}", self.0)
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+pub struct AchievementRegistry {
+    achievements: Vec<Achievement>,
+}
+
+impl AchievementRegistry {
+    /// The achievements embedded in the game
+    pub fn builtin() -> &'static Self {
+        static REGISTRY: OnceLock<AchievementRegistry> = OnceLock::new();
+
+        REGISTRY.get_or_init(|| Self::from_json(BUILTIN_ACHIEVEMENTS).unwrap())
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, RegistryError> {
+        let achievements: Vec<Achievement> =
+            serde_json::from_str(json).map_err(|e| RegistryError(e.to_string()))?;
+
+        for (index, achievement) in achievements.iter().enumerate() {
+            if achievements[..index].iter().any(|a| a.id == achievement.id) {
+                return Err(RegistryError(format!("duplicate id {}", achievement.id)));
+            }
+            if achievement.goal.target() == 0 {
+                return Err(RegistryError(format!(
+                    "{} is unlocked without playing",
+                    achievement.id
+                )));
+            }
+        }
+
+        Ok(Self { achievements })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Achievement> {
+        self.achievements.iter()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Achievement> {
+        self.achievements.iter().find(|a| a.id == id)
+    }
+}
+
+/// What is kept between sessions, by achievement id
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AchievementProgress {
+    pub unlocked: BTreeSet<String>,
+    /// Best value reached so far, or the total for goals counted over all runs
+    pub counters: BTreeMap<String, u64>,
+}
+
+impl AchievementProgress {
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.unlocked.contains(id)
+    }
+
+    /// Progress towards the goal, capped at its target
+    pub fn progress(&self, achievement: &Achievement) -> u64 {
+        let target = achievement.goal.target();
+        if self.is_unlocked(&achievement.id) {
+            return target;
+        }
+        self.counters
+            .get(&achievement.id)
+            .copied()
+            .unwrap_or(0)
+            .min(target)
+    }
+}
+
+enum Step {
+    /// The counter keeps the best value
+    Best(u64),
+    /// The value is added to the counter
+    Add(u64),
+}
+
+/// Follows a run and unlocks achievements, each hook returns those it just unlocked
+pub struct AchievementTracker<'a> {
+    registry: &'a AchievementRegistry,
+    pub progress: AchievementProgress,
+    /// Levels the current run is played with
+    levels: UpgradeLevels,
+    /// Enemies pocketed since the round was launched
+    pocketed: u64,
+    /// Merges since the round was launched
+    merged: u64,
+}
+
+impl<'a> AchievementTracker<'a> {
+    pub fn new(registry: &'a AchievementRegistry, progress: AchievementProgress) -> Self {
+        Self {
+            registry,
+            progress,
+            levels: UpgradeLevels::default(),
+            pocketed: 0,
+            merged: 0,
+        }
+    }
+
+    pub fn start_run(&mut self, levels: &UpgradeLevels) {
+        self.levels = levels.clone();
+        self.pocketed = 0;
+        self.merged = 0;
+    }
+
+    /// The shots of a round were launched, one shot goals start over
+    pub fn round_launched(&mut self) {
+        self.pocketed = 0;
+        self.merged = 0;
+    }
+
+    /// What happened during a tick of the world
+    pub fn on_tick(&mut self, events: &[Event]) -> Vec<&'a Achievement> {
+        let mut pocketed = 0;
+        let mut coins = 0;
+        for event in events {
+            match event {
+                Event::Pocketed {
+                    kind: BallType::Enemy(_),
+                    ..
+                } => pocketed += 1,
+                Event::Merged { .. } => self.merged += 1,
+                Event::CoinCollected { .. } => coins += 1,
+                _ => {}
+            }
+        }
+        self.pocketed += pocketed;
+
+        let (round_pocketed, round_merged) = (self.pocketed, self.merged);
+        self.advance(|goal| match goal {
+            Goal::PocketedInOneShot { .. } => Some(Step::Best(round_pocketed)),
+            Goal::MergedInOneRound { .. } => Some(Step::Best(round_merged)),
+            Goal::PocketedTotal { .. } => Some(Step::Add(pocketed)),
+            Goal::CoinsTotal { .. } => Some(Step::Add(coins)),
+            _ => None,
+        })
+    }
+
+    /// The world spawned the round it is now at
+    pub fn on_spawn_round(&mut self, round: usize) -> Vec<&'a Achievement> {
+        let levels = self.levels.clone();
+        self.advance(|goal| match goal {
+            Goal::Round { .. } => Some(Step::Best(round as u64)),
+            Goal::RoundWithout { upgrade, .. } if levels.get(upgrade) == 0 => {
+                Some(Step::Best(round as u64))
+            }
+            _ => None,
+        })
+    }
+
+    /// Upgrades were bought in the shop, these are the new levels
+    pub fn on_purchase(&mut self, levels: &UpgradeLevels) -> Vec<&'a Achievement> {
+        let maxed = UpgradeRegistry::builtin()
+            .iter()
+            .any(|upgrade| levels.get(&upgrade.id) >= upgrade.max_level);
+        self.advance(|goal| match goal {
+            Goal::MaxedUpgrade => Some(Step::Best(maxed as u64)),
+            _ => None,
+        })
+    }
+
+    fn advance(&mut self, mut step: impl FnMut(&Goal) -> Option<Step>) -> Vec<&'a Achievement> {
+        let mut unlocked = Vec::new();
+
+        for achievement in self.registry.iter() {
+            if self.progress.is_unlocked(&achievement.id) {
+                continue;
+            }
+            let Some(step) = step(&achievement.goal) else {
+                continue;
+            };
+
+            let counter = self
+                .progress
+                .counters
+                .entry(achievement.id.clone())
+                .or_default();
+            match step {
+                Step::Best(value) => *counter = (*counter).max(value),
+                Step::Add(value) => *counter += value,
+            }
+
+            if *counter >= achievement.goal.target() {
+                self.progress.counters.remove(&achievement.id);
+                self.progress.unlocked.insert(achievement.id.clone());
+                unlocked.push(achievement);
+            }
+        }
+
+        unlocked
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+
+use crate::entity::{Archetype, BallId, PlayerId};
+use crate::events::Event;
+use crate::rng::Rng;
+use crate::solver::Solver;
+use crate::table::Table;
+use crate::upgrades::UpgradeLevels;
+use crate::world::{MAX_AIM, World};
+use glam::Vec2;
+use serde::Serialize;
+
+/// Simulated runs are played alone
+const PLAYER: PlayerId = PlayerId(0);
+
+/// Picks the shot of each round of a simulated run
+pub trait Policy {
+    fn moves(&mut self, world: &World) -> BTreeMap<BallId, Vec2>;
+}
+
+/// Shoots one slime picked at random, in a random direction and strength
+pub struct RandomPolicy {
+    rng: Rng,
+}
+
+impl RandomPolicy {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+        }
+    }
+}
+
+impl Policy for RandomPolicy {
+    fn moves(&mut self, world: &World) -> BTreeMap<BallId, Vec2> {
+        let slimes = slimes(world);
+        if slimes.is_empty() {
+            return BTreeMap::new();
+        }
+
+        let slime = slimes[self.rng.rand_range(0., slimes.len() as f64) as usize];
+        let angle = self.rng.rand_range(0., std::f64::consts::TAU) as f32;
+        let strength = self.rng.rand_range(0.2, 1.) as f32 * MAX_AIM;
+        BTreeMap::from([(slime, Vec2::from_angle(angle) * strength)])
+    }
+}
+
+/// Shoots the single slime whose best shot scores the most this round, ignoring later rounds
+pub struct GreedyPolicy {
+    solver: Solver,
+}
+
+impl GreedyPolicy {
+    pub fn new() -> Self {
+        Self {
+            solver: Solver::hint(),
+        }
+    }
+}
+
+impl Default for GreedyPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Policy for GreedyPolicy {
+    fn moves(&mut self, world: &World) -> BTreeMap<BallId, Vec2> {
+        let mut best = (f32::NEG_INFINITY, BTreeMap::new());
+        for slime in slimes(world) {
+            for aim in self.solver.aims() {
+                let moves = BTreeMap::from([(slime, aim)]);
+                let score = Solver::simulate(world, PLAYER, &moves).score();
+                if score > best.0 {
+                    best = (score, moves);
+                }
+            }
+        }
+        best.1
+    }
+}
+
+impl Policy for Solver {
+    fn moves(&mut self, world: &World) -> BTreeMap<BallId, Vec2> {
+        self.solve(world, PLAYER)
+            .map(|solution| solution.moves)
+            .unwrap_or_default()
+    }
+}
+
+fn slimes(world: &World) -> Vec<BallId> {
+    world
+        .balls
+        .iter()
+        .map(|ball| ball.borrow())
+        .filter(|ball| ball.letypedelaboule.is_player())
+        .map(|ball| ball.id)
+        .collect()
+}
+
+/// Why a run ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Death {
+    /// The timer of an enemy of this kind ran out
+    Timer { archetype: Archetype },
+    /// Every slime fell in a pocket
+    NoSlimes,
+    /// No free spot was left to spawn a coin or an enemy
+    TableFull,
+}
+
+impl Death {
+    pub fn label(&self) -> String {
+        match self {
+            Self::Timer { archetype } => format!("timer_{archetype:?}").to_lowercase(),
+            Self::NoSlimes => "no_slimes".to_owned(),
+            Self::TableFull => "table_full".to_owned(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct RoundStats {
+    /// Money earned during the round
+    pub money: u64,
+    /// Balls on the table once the round was spawned
+    pub slimes: usize,
+    pub enemies: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RunStats {
+    pub seed: u64,
+    pub rounds: usize,
+    pub money: u64,
+    /// None when the run was still going after the round limit
+    pub death: Option<Death>,
+    pub per_round: Vec<RoundStats>,
+}
+
+impl RunStats {
+    pub const CSV_HEADER: &str =
+        "seed,rounds,money,death,money_per_round,slimes_per_round,enemies_per_round";
+
+    /// One CSV line, the per round columns are lists separated by `;`
+    pub fn csv_row(&self) -> String {
+        let list = |value: fn(&RoundStats) -> String| {
+            self.per_round
+                .iter()
+                .map(value)
+                .collect::<Vec<_>>()
+                .join(";")
+        };
+
+        format!(
+            "{},{},{},{},{},{},{}",
+            self.seed,
+            self.rounds,
+            self.money,
+            self.death
+                .map_or("survived".to_owned(), |death| death.label()),
+            list(|round| round.money.to_string()),
+            list(|round| round.slimes.to_string()),
+            list(|round| round.enemies.to_string()),
+        )
+    }
+}
+
+/// Play a whole run with the policy, stopping after `max_rounds` if it still goes on
+pub fn simulate_run(
+    seed: u64,
+    levels: &UpgradeLevels,
+    table: &Table,
+    policy: &mut dyn Policy,
+    max_rounds: usize,
+) -> RunStats {
+    let mut world = World::new(seed, levels, table);
+    let mut stats = RunStats {
+        seed,
+        rounds: 0,
+        money: 0,
+        death: None,
+        per_round: Vec::new(),
+    };
+
+    while !world.is_game_over() && world.round < max_rounds {
+        let money = world.money;
+        let moves = policy.moves(&world);
+        world
+            .launch_round(PLAYER, &moves)
+            .expect("policies only shoot balls on the table");
+        while world.tick().0 {}
+
+        if world.is_game_over() {
+            stats.death = Some(Death::NoSlimes);
+        } else {
+            let events = world.spawn_round();
+            if world.is_game_over() {
+                let timer = events.iter().find_map(|event| match event {
+                    Event::TimerExpired { archetype, .. } if *archetype != Archetype::Bomb => {
+                        Some(*archetype)
+                    }
+                    _ => None,
+                });
+                stats.death = Some(match timer {
+                    Some(archetype) => Death::Timer { archetype },
+                    None => Death::TableFull,
+                });
+            }
+        }
+
+        let (slimes, enemies) = world
+            .balls
+            .iter()
+            .partition::<Vec<_>, _>(|ball| ball.borrow().letypedelaboule.is_player());
+        stats.per_round.push(RoundStats {
+            money: world.money - money,
+            slimes: slimes.len(),
+            enemies: enemies.len(),
+        });
+    }
+
+    stats.rounds = world.round;
+    stats.money = world.money;
+    stats
+}
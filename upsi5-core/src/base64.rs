@@ -0,0 +1,123 @@
+//! Bytes written as base64 text in JSON, for `#[serde(with = "crate::base64")]`. Serde writes a
+//! `Vec<u8>` as an array of numbers, about four times the size of the bytes.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const PADDING: u8 = b'=';
+
+pub fn encode(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (index, &byte)| {
+                group | u32::from(byte) << (16 - 8 * index)
+            });
+        for index in 0..4 {
+            let digit = if index <= chunk.len() {
+                ALPHABET[(group >> (18 - 6 * index) & 63) as usize]
+            } else {
+                PADDING
+            };
+            text.push(digit as char);
+        }
+    }
+
+    text
+}
+
+/// None when the text isn't padded base64
+pub fn decode(text: &str) -> Option<Vec<u8>> {
+    let text = text.as_bytes();
+    if !text.len().is_multiple_of(4) {
+        return None;
+    }
+
+    let groups = text.len() / 4;
+    let mut bytes = Vec::with_capacity(groups * 3);
+    for (group_index, chunk) in text.chunks(4).enumerate() {
+        let padding = chunk
+            .iter()
+            .rev()
+            .take_while(|&&digit| digit == PADDING)
+            .count();
+        if padding > 2 || padding > 0 && group_index + 1 < groups {
+            return None;
+        }
+
+        let mut group = 0u32;
+        for (index, digit) in chunk[..4 - padding].iter().enumerate() {
+            let value = ALPHABET.iter().position(|letter| letter == digit)? as u32;
+            group |= value << (18 - 6 * index);
+        }
+        bytes.extend_from_slice(&group.to_be_bytes()[1..4 - padding]);
+    }
+
+    Some(bytes)
+}
+
+pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&encode(bytes))
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    decode(&text).ok_or_else(|| D::Error::custom("invalid base64"))
+}
+
+/// For `Option<Vec<u8>>` fields
+pub mod option {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        bytes: &Option<Vec<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match bytes {
+            Some(bytes) => super::serialize(bytes, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<u8>>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|text| decode(&text).ok_or_else(|| D::Error::custom("invalid base64")))
+            .transpose()
+    }
+}
+
+/// For maps of bytes by name
+pub mod map {
+    use super::*;
+    use serde::ser::SerializeMap;
+
+    pub fn serialize<S: Serializer>(
+        map: &BTreeMap<String, Vec<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut encoded = serializer.serialize_map(Some(map.len()))?;
+        for (key, bytes) in map {
+            encoded.serialize_entry(key, &encode(bytes))?;
+        }
+        encoded.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<String, Vec<u8>>, D::Error> {
+        BTreeMap::<String, String>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(key, text)| match decode(&text) {
+                Some(bytes) => Ok((key, bytes)),
+                None => Err(D::Error::custom("invalid base64")),
+            })
+            .collect()
+    }
+}
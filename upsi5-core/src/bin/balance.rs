@@ -0,0 +1,191 @@
+//! Simulates many complete runs with a shot policy and writes one line of statistics per run.
+//!
+//! ```text
+//! balance [--runs N] [--policy random|greedy|solver] [--level ID=LEVEL]... [--table ID]
+//!         [--seed SEED] [--max-rounds N] [--format csv|json] [--threads N]
+//! ```
+
+use std::{env, fmt, process::ExitCode, thread};
+
+use upsi5_core::{
+    balance::{self, GreedyPolicy, Policy, RandomPolicy, RunStats},
+    solver::Solver,
+    table::TableRegistry,
+    upgrades::{UpgradeLevels, UpgradeRegistry},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PolicyKind {
+    Random,
+    Greedy,
+    Solver,
+}
+
+impl PolicyKind {
+    fn build(self, seed: u64) -> Box<dyn Policy> {
+        match self {
+            Self::Random => Box::new(RandomPolicy::new(seed)),
+            Self::Greedy => Box::new(GreedyPolicy::new()),
+            Self::Solver => Box::new(Solver::default()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Csv,
+    Json,
+}
+
+#[derive(Debug)]
+struct ArgsError(String);
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ArgsError {}
+
+struct Config {
+    runs: u64,
+    policy: PolicyKind,
+    levels: UpgradeLevels,
+    table: String,
+    seed: u64,
+    max_rounds: usize,
+    format: Format,
+    threads: usize,
+}
+
+impl Config {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, ArgsError> {
+        let mut config = Self {
+            runs: 1000,
+            policy: PolicyKind::Random,
+            levels: UpgradeLevels::default(),
+            table: TableRegistry::builtin().default_table().id.clone(),
+            seed: 0,
+            max_rounds: 100,
+            format: Format::Csv,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        };
+
+        while let Some(flag) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| ArgsError(format!("{flag} needs a value")))?;
+            let number = |value: &str| {
+                value
+                    .parse::<u64>()
+                    .map_err(|_| ArgsError(format!("{flag} expects a number, got {value}")))
+            };
+
+            match flag.as_str() {
+                "--runs" => config.runs = number(&value)?,
+                "--seed" => config.seed = number(&value)?,
+                "--max-rounds" => config.max_rounds = number(&value)? as usize,
+                "--threads" => config.threads = (number(&value)? as usize).max(1),
+                "--policy" => {
+                    config.policy = match value.as_str() {
+                        "random" => PolicyKind::Random,
+                        "greedy" => PolicyKind::Greedy,
+                        "solver" => PolicyKind::Solver,
+                        _ => return Err(ArgsError(format!("unknown policy {value}"))),
+                    }
+                }
+                "--format" => {
+                    config.format = match value.as_str() {
+                        "csv" => Format::Csv,
+                        "json" => Format::Json,
+                        _ => return Err(ArgsError(format!("unknown format {value}"))),
+                    }
+                }
+                "--table" => {
+                    if TableRegistry::builtin().get(&value).is_none() {
+                        return Err(ArgsError(format!("unknown table {value}")));
+                    }
+                    config.table = value;
+                }
+                "--level" => {
+                    let (id, level) = value
+                        .split_once('=')
+                        .ok_or_else(|| ArgsError(format!("expected ID=LEVEL, got {value}")))?;
+                    let upgrade = UpgradeRegistry::builtin()
+                        .get(id)
+                        .ok_or_else(|| ArgsError(format!("unknown upgrade {id}")))?;
+                    let level = number(level)? as usize;
+                    if level > upgrade.max_level {
+                        return Err(ArgsError(format!(
+                            "{id} only goes up to level {}",
+                            upgrade.max_level
+                        )));
+                    }
+                    config.levels.set(id, level);
+                }
+                _ => return Err(ArgsError(format!("unknown option {flag}"))),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+fn run_all(config: &Config) -> Vec<RunStats> {
+    let table = TableRegistry::builtin().get(&config.table).unwrap();
+    let seeds: Vec<u64> = (0..config.runs).map(|run| config.seed + run).collect();
+    let chunk = seeds.len().div_ceil(config.threads).max(1);
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = seeds
+            .chunks(chunk)
+            .map(|seeds| {
+                scope.spawn(move || {
+                    seeds
+                        .iter()
+                        .map(|&seed| {
+                            let mut policy = config.policy.build(seed);
+                            balance::simulate_run(
+                                seed,
+                                &config.levels,
+                                table,
+                                policy.as_mut(),
+                                config.max_rounds,
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect()
+    })
+}
+
+fn main() -> ExitCode {
+    let config = match Config::from_args(env::args().skip(1)) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("balance: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let runs = run_all(&config);
+
+    match config.format {
+        Format::Csv => {
+            println!("{}", RunStats::CSV_HEADER);
+            for run in &runs {
+                println!("{}", run.csv_row());
+            }
+        }
+        Format::Json => println!("{}", serde_json::to_string(&runs).unwrap()),
+    }
+
+    ExitCode::SUCCESS
+}
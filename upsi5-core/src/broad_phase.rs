@@ -0,0 +1,104 @@
+use glam::Vec2;
+
+/// Uniform grid over the table, items are stored in every cell their bounding box covers.
+/// Items outside of the grid are clamped into the border cells.
+#[derive(Clone)]
+pub struct Grid {
+    cell_size: f32,
+    cols: usize,
+    rows: usize,
+    cells: Vec<Vec<usize>>,
+}
+
+impl Grid {
+    pub fn new(dimensions: Vec2, cell_size: f32) -> Self {
+        let cols = (dimensions.x / cell_size).ceil().max(1.) as usize;
+        let rows = (dimensions.y / cell_size).ceil().max(1.) as usize;
+
+        Self {
+            cell_size,
+            cols,
+            rows,
+            cells: vec![Vec::new(); cols * rows],
+        }
+    }
+
+    /// Empty the grid, only reallocating the cells when their size changed
+    pub fn reset(&mut self, dimensions: Vec2, cell_size: f32) {
+        if cell_size == self.cell_size {
+            self.clear();
+        } else {
+            *self = Self::new(dimensions, cell_size);
+        }
+    }
+
+    /// Empty every cell but keep their allocations for the next tick
+    pub fn clear(&mut self) {
+        for cell in &mut self.cells {
+            cell.clear();
+        }
+    }
+
+    fn cell_range(&self, min: Vec2, max: Vec2) -> (usize, usize, usize, usize) {
+        let to_cell = |value: f32, count: usize| {
+            ((value / self.cell_size).floor().max(0.) as usize).min(count - 1)
+        };
+
+        (
+            to_cell(min.x, self.cols),
+            to_cell(max.x, self.cols),
+            to_cell(min.y, self.rows),
+            to_cell(max.y, self.rows),
+        )
+    }
+
+    pub fn insert(&mut self, item: usize, min: Vec2, max: Vec2) {
+        let (x1, x2, y1, y2) = self.cell_range(min, max);
+
+        for y in y1..=y2 {
+            for x in x1..=x2 {
+                self.cells[y * self.cols + x].push(item);
+            }
+        }
+    }
+
+    /// Items sharing a cell with the given box, sorted and without duplicates
+    pub fn query(&self, min: Vec2, max: Vec2, result: &mut Vec<usize>) {
+        result.clear();
+
+        let (x1, x2, y1, y2) = self.cell_range(min, max);
+
+        for y in y1..=y2 {
+            for x in x1..=x2 {
+                result.extend_from_slice(&self.cells[y * self.cols + x]);
+            }
+        }
+
+        result.sort_unstable();
+        result.dedup();
+    }
+
+    /// Items stored in the cell containing `point`
+    pub fn at(&self, point: Vec2) -> &[usize] {
+        let (x, _, y, _) = self.cell_range(point, point);
+
+        &self.cells[y * self.cols + x]
+    }
+
+    /// Every `(a, b)` with `a < b` sharing at least one cell, sorted and without duplicates
+    pub fn pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+
+        for cell in &self.cells {
+            for (i, &a) in cell.iter().enumerate() {
+                for &b in &cell[i + 1..] {
+                    pairs.push((a.min(b), a.max(b)));
+                }
+            }
+        }
+
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+}
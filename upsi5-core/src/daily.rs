@@ -0,0 +1,105 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::rng::Rng;
+use crate::table::{Table, TableRegistry};
+use crate::upgrades::{UpgradeLevels, UpgradeRegistry};
+
+const MILLIS_PER_DAY: f64 = 86_400_000.;
+/// Mixed into the date so the daily seeds don't follow the seeds of normal runs
+const DAILY_SALT: u64 = 0x5D41_1E5E_ED00_0000;
+
+/// Day of the calendar, taken in UTC so everyone plays the same challenge at the same time
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Date {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    /// Date of the `days`th day after the first of January 1970
+    pub fn from_days_since_epoch(days: i64) -> Self {
+        // Days since the first of March of year 0, years then start with March so the leap
+        // day is the last day of a year
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_from_march = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+        let month = if month_from_march < 10 {
+            month_from_march + 3
+        } else {
+            month_from_march - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        Self {
+            year: year as i32,
+            month: month as u8,
+            day: day as u8,
+        }
+    }
+
+    /// Date of a timestamp in milliseconds since the Unix epoch, as given by the browser clock
+    pub fn from_unix_millis(millis: f64) -> Self {
+        Self::from_days_since_epoch((millis / MILLIS_PER_DAY).floor() as i64)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// The run everyone plays on a given day: same seed, same upgrades and same table
+#[derive(Clone, Debug, PartialEq)]
+pub struct DailyChallenge {
+    pub date: Date,
+    pub seed: u64,
+    pub levels: UpgradeLevels,
+    pub table: &'static Table,
+}
+
+impl DailyChallenge {
+    pub fn new(date: Date) -> Self {
+        let day_number =
+            date.year as u64 * 10_000 + u64::from(date.month) * 100 + u64::from(date.day);
+        let mut rng = Rng::new(DAILY_SALT ^ day_number);
+
+        let seed = rng.next_u64();
+
+        let mut levels = UpgradeLevels::default();
+        for upgrade in UpgradeRegistry::builtin().iter() {
+            let level = rng.rand_range(0., (upgrade.max_level + 1) as f64) as usize;
+            levels.set(&upgrade.id, level);
+        }
+
+        let tables = TableRegistry::builtin();
+        let table_count = tables.iter().count();
+        let table = tables
+            .iter()
+            .nth(rng.rand_range(0., table_count as f64) as usize)
+            .unwrap();
+
+        Self {
+            date,
+            seed,
+            levels,
+            table,
+        }
+    }
+}
+
+/// How far the single attempt of a daily challenge went
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub date: Date,
+    pub round: usize,
+    pub money: u64,
+}
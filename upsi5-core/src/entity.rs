@@ -0,0 +1,128 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+
+/// Identifies a ball for its whole life, unlike its index in `World::balls`
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct BallId(pub u64);
+
+#[derive(Clone, Default)]
+pub struct BallIdAllocator {
+    next: Cell<u64>,
+}
+
+impl BallIdAllocator {
+    pub fn allocate(&self) -> BallId {
+        let id = self.next.get();
+        self.next.set(id + 1);
+        BallId(id)
+    }
+}
+
+/// One of the players sharing the table, the only one outside of versus runs is `PlayerId(0)`
+#[derive(
+    Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize,
+)]
+pub struct PlayerId(pub u8);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BallType {
+    /// A slime and the player it belongs to
+    Player(PlayerId),
+    Enemy(EnemyData),
+}
+
+impl BallType {
+    pub const fn is_player(&self) -> bool {
+        matches!(self, Self::Player(_))
+    }
+
+    pub const fn owner(&self) -> Option<PlayerId> {
+        match self {
+            Self::Player(owner) => Some(*owner),
+            Self::Enemy(_) => None,
+        }
+    }
+}
+
+/// Kinds of enemies, each with its own body and behaviour on the table
+#[derive(
+    Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Archetype {
+    #[default]
+    Basic,
+    /// Heavy and slow, hard to push into a hole
+    Anchor,
+    /// Breaks in two basic enemies when a slime hits it
+    Splitter,
+    /// Pushes nearby balls away when its timer ends instead of ending the run
+    Bomb,
+    /// Can only fall in a hole once a slime broke its shield
+    Shielded,
+    /// Goes through other enemies
+    Ghost,
+}
+
+impl Archetype {
+    pub const ALL: [Self; 6] = [
+        Self::Basic,
+        Self::Anchor,
+        Self::Splitter,
+        Self::Bomb,
+        Self::Shielded,
+        Self::Ghost,
+    ];
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EnemyData {
+    pub timer: usize,
+    pub archetype: Archetype,
+    /// Only meaningful for shielded enemies
+    pub shield: bool,
+}
+
+impl EnemyData {
+    pub const fn new(archetype: Archetype, timer: usize) -> Self {
+        Self {
+            timer,
+            archetype,
+            shield: matches!(archetype, Archetype::Shielded),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Ball {
+    pub id: BallId,
+    pub mass: f32,
+    pub position: Vec2,
+    pub speed: Vec2,
+    pub friction_coeff: f32,
+    pub radius: f32,
+    pub letypedelaboule: BallType,
+}
+
+impl Ball {
+    pub fn new(
+        id: BallId,
+        mass: f32,
+        position: Vec2,
+        speed: Vec2,
+        friction_coeff: f32,
+        radius: f32,
+        letypedelaboule: BallType,
+    ) -> Ball {
+        Ball {
+            id,
+            mass,
+            position,
+            speed,
+            friction_coeff,
+            radius,
+            letypedelaboule,
+        }
+    }
+}
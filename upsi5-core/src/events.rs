@@ -0,0 +1,97 @@
+use crate::entity::{Archetype, BallId, BallType};
+use crate::items::ItemKind;
+use crate::scoring::{ComboKind, RoundScore};
+use crate::world::Sounds;
+use glam::Vec2;
+
+/// Something that happened on the table during a tick or a `spawn_round`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    /// Two balls bounced off each other, not sent when slimes merge
+    Collision {
+        balls: [BallId; 2],
+        kinds: [BallType; 2],
+        point: Vec2,
+        impulse: f32,
+    },
+    /// A ball bounced off the border, or off the obstacle at this index in `World::obstacles`
+    Bounce {
+        ball: BallId,
+        obstacle: Option<usize>,
+        point: Vec2,
+        impulse: f32,
+    },
+    /// A ball fell in the pocket at this index in `Table::pockets`
+    Pocketed {
+        ball: BallId,
+        kind: BallType,
+        pocket: usize,
+    },
+    CoinCollected {
+        ball: BallId,
+        position: Vec2,
+        value: u64,
+    },
+    PowerUpCollected {
+        ball: BallId,
+        kind: ItemKind,
+        position: Vec2,
+    },
+    /// Two slimes became one
+    Merged {
+        balls: [BallId; 2],
+        into: BallId,
+        position: Vec2,
+    },
+    /// A slime broke a splitter in two
+    Split {
+        ball: BallId,
+        into: [BallId; 2],
+        position: Vec2,
+    },
+    /// A slime broke the shield of an enemy
+    ShieldBroken { ball: BallId, position: Vec2 },
+    /// The timer of an enemy reached zero, bombs explode and any other enemy ends the run
+    TimerExpired {
+        ball: BallId,
+        archetype: Archetype,
+        position: Vec2,
+    },
+    /// A combo raised the multiplier of the round
+    Combo {
+        kind: ComboKind,
+        position: Vec2,
+        multiplier: u64,
+    },
+    /// The launched round came to rest and its bonus was paid, sent before `spawn_round`
+    RoundScored { score: RoundScore },
+}
+
+impl Event {
+    pub fn sound(&self) -> Option<Sounds> {
+        match self {
+            Self::Collision {
+                kinds: [BallType::Enemy(_), BallType::Enemy(_)],
+                ..
+            } => Some(Sounds::EnemyEnemy),
+            Self::Collision { .. } => Some(Sounds::SlimeEnemy),
+            Self::Bounce { .. } => Some(Sounds::Border),
+            Self::Pocketed { .. } => Some(Sounds::Falling),
+            Self::CoinCollected { .. } | Self::PowerUpCollected { .. } => Some(Sounds::Coin),
+            Self::Merged { .. } => Some(Sounds::SlimeSlime),
+            Self::Split { .. }
+            | Self::ShieldBroken { .. }
+            | Self::TimerExpired { .. }
+            | Self::Combo { .. }
+            | Self::RoundScored { .. } => None,
+        }
+    }
+
+    /// Impulse magnitude of a contact, zero for events that aren't one
+    pub fn impulse(&self) -> f32 {
+        match self {
+            Self::Collision { impulse, .. } | Self::Bounce { impulse, .. } => *impulse,
+            _ => 0.,
+        }
+    }
+}
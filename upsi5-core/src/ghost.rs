@@ -0,0 +1,64 @@
+//! A previous run raced next to the live one. The ghost only plays the shots of a round once the
+//! player launched theirs, then both worlds are ticked together.
+
+use crate::replay::{Playback, Replay, ReplayError};
+use crate::world::World;
+
+pub struct Ghost {
+    /// Who played the run
+    pub name: String,
+    playback: Playback,
+    /// The ghost launched its round and ticks with the live world
+    running: bool,
+}
+
+impl Ghost {
+    pub fn new(name: impl Into<String>, replay: Replay) -> Self {
+        Self {
+            name: name.into(),
+            playback: Playback::new(replay),
+            running: false,
+        }
+    }
+
+    pub fn world(&self) -> &World {
+        &self.playback.world
+    }
+
+    pub fn replay(&self) -> &Replay {
+        self.playback.replay()
+    }
+
+    /// Play the recorded shots of the round the player just launched. The ghost first finishes
+    /// its current round and catches up, or starts over when an undo sent the player back.
+    /// Nothing is launched once the recorded run is over.
+    pub fn launch(&mut self, round: usize) -> Result<(), ReplayError> {
+        let world = &self.playback.world;
+        if world.round > round || world.round == round && self.playback.is_running() {
+            self.playback = Playback::new(self.playback.replay().clone());
+        }
+
+        while !self.playback.is_finished()
+            && (self.playback.is_running() || self.playback.world.round < round)
+        {
+            self.playback.tick()?;
+        }
+
+        self.running = !self.playback.is_finished() && self.playback.world.round == round;
+        Ok(())
+    }
+
+    /// Advance the ghost by one tick of the live world, true while its round runs
+    pub fn tick(&mut self) -> Result<bool, ReplayError> {
+        if self.running {
+            let (round_ended, _) = self.playback.tick()?;
+            self.running = !round_ended;
+        }
+        Ok(self.running)
+    }
+
+    /// Money the player has over the ghost, negative when behind
+    pub fn money_ahead(&self, money: u64) -> i64 {
+        money as i64 - self.playback.world.money as i64
+    }
+}
@@ -0,0 +1,84 @@
+//! Things lying on the table that slimes pick up by touching them: coins and power-ups.
+
+use glam::Vec2;
+use serde::Deserialize;
+
+use crate::entity::BallId;
+use crate::world::COIN_RADIUS;
+
+/// Radius of every power-up
+pub const POWER_UP_RADIUS: f32 = 0.018;
+/// Coins closer than this to a magnetic slime are pulled towards it
+pub const MAGNET_RADIUS: f32 = 0.25;
+/// Distance a pulled coin moves each tick
+pub const MAGNET_PULL: f32 = 0.0015;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    Coin,
+    /// The slime picking it up pulls the nearby coins for the rest of the round
+    Magnet,
+    /// Enemy timers don't go down at the end of the round
+    Freeze,
+    /// The next coin picked up is worth double
+    DoubleCoin,
+    /// The slime picking it up splits in three pieces instead of two halves the next time it's
+    /// launched
+    TripleSplit,
+}
+
+impl ItemKind {
+    pub const POWER_UPS: [Self; 4] = [
+        Self::Magnet,
+        Self::Freeze,
+        Self::DoubleCoin,
+        Self::TripleSplit,
+    ];
+
+    pub fn radius(self) -> f32 {
+        match self {
+            Self::Coin => COIN_RADIUS,
+            _ => POWER_UP_RADIUS,
+        }
+    }
+
+    /// Name of the texture the item is drawn with
+    pub fn texture(self) -> &'static str {
+        match self {
+            Self::Coin => "coin",
+            Self::Magnet => "magnet",
+            Self::Freeze => "freeze",
+            Self::DoubleCoin => "doublecoin",
+            Self::TripleSplit => "triplesplit",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Item {
+    pub kind: ItemKind,
+    pub position: Vec2,
+}
+
+impl Item {
+    pub fn coin(position: Vec2) -> Self {
+        Self {
+            kind: ItemKind::Coin,
+            position,
+        }
+    }
+
+    pub fn radius(&self) -> f32 {
+        self.kind.radius()
+    }
+}
+
+/// Power-ups picked up by one player and waiting to be used
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PowerUps {
+    /// Coins left to pick up at double value
+    pub doubled_coins: u32,
+    /// Slimes splitting in three the next time they're launched, once per entry
+    pub triple_splits: Vec<BallId>,
+}
@@ -0,0 +1,96 @@
+//! Messages of the leaderboard. Scores are only accepted with the replay of their run, which the
+//! server plays again to check the claimed round and money.
+
+use serde::{Deserialize, Serialize};
+
+use crate::online::ProtocolError;
+
+pub const DEFAULT_LEADERBOARD_PORT: u16 = 9002;
+/// Most scores a single request can fetch
+pub const MAX_TOP: usize = 100;
+pub const MAX_NAME_LENGTH: usize = 16;
+
+/// Best run of a player, better runs reach a later round then earn more money
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Score {
+    pub name: String,
+    pub round: usize,
+    pub money: u64,
+    pub seed: u64,
+}
+
+impl Score {
+    pub fn beats(&self, other: &Self) -> bool {
+        (self.round, self.money) > (other.round, other.money)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LeaderboardRequest {
+    /// A finished run and the replay proving it, as written by `Replay::to_bytes`
+    Submit {
+        score: Score,
+        #[serde(with = "crate::base64")]
+        replay: Vec<u8>,
+    },
+    Top {
+        count: usize,
+    },
+    Rank {
+        name: String,
+    },
+    /// The replay of the best run of the player, to race it as a ghost
+    Replay {
+        name: String,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LeaderboardResponse {
+    /// Rank of the player after the submission, starting at 1
+    Submitted {
+        rank: usize,
+    },
+    /// The replay doesn't prove the score
+    Rejected {
+        reason: String,
+    },
+    Top {
+        scores: Vec<Score>,
+    },
+    /// None when the player has no score yet
+    Rank {
+        rank: Option<usize>,
+        score: Option<Score>,
+    },
+    Replay {
+        score: Score,
+        #[serde(with = "crate::base64")]
+        replay: Vec<u8>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl LeaderboardRequest {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ProtocolError> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+impl LeaderboardResponse {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ProtocolError> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
@@ -0,0 +1,23 @@
+pub mod achievements;
+pub mod balance;
+pub mod base64;
+pub mod broad_phase;
+pub mod daily;
+pub mod entity;
+pub mod events;
+pub mod ghost;
+pub mod items;
+pub mod leaderboard;
+pub mod obstacles;
+pub mod online;
+pub mod physics;
+pub mod preview;
+pub mod replay;
+pub mod rng;
+pub mod save;
+pub mod scoring;
+pub mod solver;
+pub mod table;
+pub mod upgrades;
+pub mod waves;
+pub mod world;
@@ -0,0 +1,193 @@
+use glam::Vec2;
+use serde::Deserialize;
+
+use crate::entity::Ball;
+use crate::physics::{Contact, PENETRATION_SLOP};
+
+pub const POST_RESTITUTION: f32 = 0.8;
+pub const WALL_RESTITUTION: f32 = 0.8;
+/// Half the width of a wall
+pub const WALL_THICKNESS: f32 = 0.006;
+
+/// Fixed shapes on the table that balls bounce off
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Obstacle {
+    Post {
+        center: Vec2,
+        radius: f32,
+    },
+    Wall {
+        start: Vec2,
+        end: Vec2,
+    },
+    /// Sends balls back faster than they came when its restitution is above 1
+    Bumper {
+        center: Vec2,
+        radius: f32,
+        restitution: f32,
+    },
+}
+
+impl Obstacle {
+    /// Point of the obstacle center, or of the wall segment, closest to `point`
+    fn core_point(&self, point: Vec2) -> Vec2 {
+        match *self {
+            Self::Post { center, .. } | Self::Bumper { center, .. } => center,
+            Self::Wall { start, end } => {
+                let segment = end - start;
+                if segment == Vec2::ZERO {
+                    return start;
+                }
+                let along = (point - start).dot(segment) / segment.length_squared();
+                start + segment * along.clamp(0., 1.)
+            }
+        }
+    }
+
+    /// Distance between the obstacle core and its surface
+    pub fn thickness(&self) -> f32 {
+        match *self {
+            Self::Post { radius, .. } | Self::Bumper { radius, .. } => radius,
+            Self::Wall { .. } => WALL_THICKNESS,
+        }
+    }
+
+    pub fn restitution(&self) -> f32 {
+        match *self {
+            Self::Post { .. } => POST_RESTITUTION,
+            Self::Wall { .. } => WALL_RESTITUTION,
+            Self::Bumper { restitution, .. } => restitution,
+        }
+    }
+
+    /// The obstacle with its positions multiplied by `scale`, its size is kept
+    pub fn scaled(&self, scale: Vec2) -> Self {
+        match *self {
+            Self::Post { center, radius } => Self::Post {
+                center: center * scale,
+                radius,
+            },
+            Self::Wall { start, end } => Self::Wall {
+                start: start * scale,
+                end: end * scale,
+            },
+            Self::Bumper {
+                center,
+                radius,
+                restitution,
+            } => Self::Bumper {
+                center: center * scale,
+                radius,
+                restitution,
+            },
+        }
+    }
+
+    /// Positions the obstacle is anchored at, its center or the ends of the wall
+    pub fn anchors(&self) -> Vec<Vec2> {
+        match *self {
+            Self::Post { center, .. } | Self::Bumper { center, .. } => vec![center],
+            Self::Wall { start, end } => vec![start, end],
+        }
+    }
+
+    pub fn overlaps(&self, center: Vec2, radius: f32) -> bool {
+        center.distance(self.core_point(center)) < radius + self.thickness()
+    }
+
+    /// Fraction of `motion` a circle starting at `start` travels before touching the obstacle.
+    /// 0 when it already overlaps and keeps going in, None when it moves away.
+    pub fn time_of_impact(&self, start: Vec2, motion: Vec2, radius: f32) -> Option<f32> {
+        let reach = radius + self.thickness();
+
+        match *self {
+            Self::Post { center, .. } | Self::Bumper { center, .. } => {
+                circle_time_of_impact(start, motion, center, reach)
+            }
+            Self::Wall { start: a, end: b } => {
+                if self.overlaps(start, radius) {
+                    let entering = motion.dot(start - self.core_point(start)) < 0.;
+                    return entering.then_some(0.);
+                }
+
+                let segment = b - a;
+                let normal = segment.perp().normalize_or_zero();
+                let offset = (start - a).dot(normal);
+                let closing = motion.dot(normal);
+
+                let mut side = None;
+                if offset.abs() > reach && offset * closing < 0. {
+                    let t = (offset.abs() - reach) / closing.abs();
+                    let along = (start + motion * t - a).dot(segment) / segment.length_squared();
+                    if t <= 1. && (0. ..=1.).contains(&along) {
+                        side = Some(t);
+                    }
+                }
+
+                [a, b]
+                    .into_iter()
+                    .filter_map(|end| circle_time_of_impact(start, motion, end, reach))
+                    .chain(side)
+                    .reduce(f32::min)
+            }
+        }
+    }
+
+    /// Bounce a ball touching the obstacle and push it out, like `physics::resolve` against a
+    /// body that never moves. The contact normal points from the obstacle to the ball.
+    pub fn bounce(&self, ball: &mut Ball) -> Option<Contact> {
+        let core = self.core_point(ball.position);
+        let offset = ball.position - core;
+        let distance = offset.length();
+        let penetration = ball.radius + self.thickness() - distance;
+
+        if penetration < -PENETRATION_SLOP {
+            return None;
+        }
+
+        let normal = if distance > 0. {
+            offset / distance
+        } else {
+            Vec2::Y
+        };
+
+        let closing_speed = ball.speed.dot(normal);
+        let mut impulse = 0.;
+
+        if closing_speed < 0. {
+            impulse = -(1. + self.restitution()) * closing_speed * ball.mass;
+            ball.speed += normal * impulse / ball.mass;
+        }
+        ball.position += normal * penetration.max(0.);
+
+        Some(Contact {
+            normal,
+            point: core + normal * self.thickness(),
+            impulse,
+        })
+    }
+}
+
+fn circle_time_of_impact(start: Vec2, motion: Vec2, center: Vec2, reach: f32) -> Option<f32> {
+    let offset = start - center;
+
+    let half_b = offset.dot(motion);
+    if half_b >= 0. {
+        return None;
+    }
+
+    let c = offset.length_squared() - reach * reach;
+    if c <= 0. {
+        return Some(0.);
+    }
+
+    let a = motion.length_squared();
+    let discriminant = half_b * half_b - a * c;
+    if discriminant < 0. {
+        return None;
+    }
+
+    let t = (-half_b - discriminant.sqrt()) / a;
+    (t <= 1.).then_some(t)
+}
@@ -0,0 +1,170 @@
+//! Messages of online matches. The relay only forwards the shots of each round, every client
+//! simulates the deterministic world itself and sends back a checksum to catch desyncs.
+
+use std::{collections::BTreeMap, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{BallId, PlayerId};
+use crate::replay::Replay;
+use crate::table::TableRegistry;
+use crate::upgrades::UpgradeLevels;
+use crate::world::{LaunchError, World};
+use glam::Vec2;
+
+/// Clients and relays only talk to each other when they share this version
+pub const PROTOCOL_VERSION: u32 = 2;
+pub const DEFAULT_RELAY_PORT: u16 = 9001;
+
+/// One slime launched during a round. The aim is sent as raw bits, a decimal number could be
+/// rounded differently by each client and desync them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shot {
+    pub ball: u64,
+    pub x: u32,
+    pub y: u32,
+}
+
+impl Shot {
+    pub fn new(ball: BallId, aim: Vec2) -> Self {
+        Self {
+            ball: ball.0,
+            x: aim.x.to_bits(),
+            y: aim.y.to_bits(),
+        }
+    }
+
+    pub fn aim(&self) -> Vec2 {
+        Vec2::new(f32::from_bits(self.x), f32::from_bits(self.y))
+    }
+}
+
+pub fn shots(moves: &BTreeMap<BallId, Vec2>) -> Vec<Shot> {
+    moves
+        .iter()
+        .map(|(&ball, &aim)| Shot::new(ball, aim))
+        .collect()
+}
+
+pub fn moves(shots: &[Shot]) -> BTreeMap<BallId, Vec2> {
+    shots
+        .iter()
+        .map(|shot| (BallId(shot.ball), shot.aim()))
+        .collect()
+}
+
+/// Launch the shots of every seat of a round, each seat only shooting its own slimes. The world is
+/// left untouched when a shot is rejected. Returns the moves of each seat, to record them.
+pub fn launch_round(
+    world: &mut World,
+    shots: &[Vec<Shot>],
+) -> Result<Vec<BTreeMap<BallId, Vec2>>, LaunchError> {
+    let mut launched = world.clone();
+    let mut seats = Vec::new();
+    for (seat, shots) in shots.iter().enumerate() {
+        let moves = moves(shots);
+        launched.launch_round(PlayerId(seat as u8), &moves)?;
+        seats.push(moves);
+    }
+
+    *world = launched;
+    Ok(seats)
+}
+
+/// Everything the clients need to build the same world
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchSetup {
+    pub seed: u64,
+    pub levels: UpgradeLevels,
+    /// Id of a built-in table
+    pub table: String,
+    pub players: u8,
+}
+
+impl MatchSetup {
+    /// An empty replay of the match, its world is the one every client starts from
+    pub fn replay(&self) -> Result<Replay, ProtocolError> {
+        let table = TableRegistry::builtin()
+            .get(&self.table)
+            .ok_or_else(|| ProtocolError(format!("unknown table {}", self.table)))?;
+        Ok(Replay::with_players(
+            self.seed,
+            self.levels.clone(),
+            table,
+            self.players,
+        ))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Wait in a room until enough players joined to start
+    Join { room: String, version: u32 },
+    /// Shots of the sender's slimes for the round
+    Moves { round: usize, shots: Vec<Shot> },
+    /// Checksum of the world once the round ended and the next one was spawned
+    Checksum { round: usize, checksum: u64 },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Start {
+        setup: MatchSetup,
+        you: PlayerId,
+    },
+    /// Shots of every player by player id, to launch together
+    Round {
+        round: usize,
+        shots: Vec<Vec<Shot>>,
+    },
+    /// The players' checksums of the round didn't match
+    Desync {
+        round: usize,
+    },
+    /// A player disconnected, the match can't go on
+    Left {
+        player: PlayerId,
+    },
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ProtocolError(pub String);
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid message: {}", self.0)
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+impl From<serde_json::Error> for ProtocolError {
+    fn from(e: serde_json::Error) -> Self {
+        Self(e.to_string())
+    }
+}
+
+impl ClientMessage {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ProtocolError> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+impl ServerMessage {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ProtocolError> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
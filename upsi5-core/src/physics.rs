@@ -0,0 +1,99 @@
+use crate::entity::{Ball, BallId};
+use glam::Vec2;
+
+/// Share of the relative speed kept after two balls bounce off each other
+pub const BALL_RESTITUTION: f32 = 0.95;
+/// Overlap tolerated before positional correction kicks in
+pub(crate) const PENETRATION_SLOP: f32 = 0.0005;
+/// Share of the remaining overlap removed on each correction
+const PENETRATION_CORRECTION: f32 = 0.8;
+
+pub struct Contact {
+    /// Unit vector pointing from the first ball to the second one
+    pub normal: Vec2,
+    pub point: Vec2,
+    /// Magnitude of the impulse exchanged, zero if the balls were already separating
+    pub impulse: f32,
+}
+
+/// Fraction of the next tick (between 0 and 1) after which `a` and `b` touch,
+/// assuming both keep their current speed. 0 when they already overlap.
+pub fn time_of_impact(a: &Ball, b: &Ball) -> Option<f32> {
+    let offset = b.position - a.position;
+    let relative_speed = b.speed - a.speed;
+    let reach = a.radius + b.radius;
+
+    let c = offset.length_squared() - reach * reach;
+    if c <= 0. {
+        return Some(0.);
+    }
+
+    let half_b = offset.dot(relative_speed);
+    if half_b >= 0. {
+        return None;
+    }
+
+    let a_coeff = relative_speed.length_squared();
+    let discriminant = half_b * half_b - a_coeff * c;
+    if discriminant < 0. {
+        return None;
+    }
+
+    let t = (-half_b - discriminant.sqrt()) / a_coeff;
+    (t <= 1.).then_some(t)
+}
+
+/// Apply the collision impulse between two touching balls and push them apart
+pub fn resolve(a: &mut Ball, b: &mut Ball, restitution: f32) -> Option<Contact> {
+    let offset = b.position - a.position;
+    let distance = offset.length();
+    let penetration = a.radius + b.radius - distance;
+
+    if penetration < -PENETRATION_SLOP {
+        return None;
+    }
+
+    let normal = if distance > 0. {
+        offset / distance
+    } else {
+        Vec2::X
+    };
+    let inv_mass_a = 1. / a.mass;
+    let inv_mass_b = 1. / b.mass;
+    let inv_mass_sum = inv_mass_a + inv_mass_b;
+
+    let closing_speed = (b.speed - a.speed).dot(normal);
+    let mut impulse = 0.;
+
+    if closing_speed < 0. {
+        impulse = -(1. + restitution) * closing_speed / inv_mass_sum;
+        a.speed -= normal * impulse * inv_mass_a;
+        b.speed += normal * impulse * inv_mass_b;
+    }
+
+    let correction =
+        normal * (penetration - PENETRATION_SLOP).max(0.) * PENETRATION_CORRECTION / inv_mass_sum;
+    a.position -= correction * inv_mass_a;
+    b.position += correction * inv_mass_b;
+
+    Some(Contact {
+        normal,
+        point: a.position + normal * a.radius,
+        impulse,
+    })
+}
+
+/// Perfectly inelastic merge, the new ball carries the momentum of both
+pub fn merge(a: &Ball, b: &Ball, id: BallId) -> Ball {
+    let mass = a.mass + b.mass;
+
+    Ball {
+        id,
+        mass,
+        position: (a.position * a.mass + b.position * b.mass) / mass,
+        speed: (a.speed * a.mass + b.speed * b.mass) / mass,
+        friction_coeff: a.friction_coeff,
+        radius: a.radius.hypot(b.radius),
+        letypedelaboule: a.letypedelaboule,
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+
+use crate::entity::{BallId, PlayerId};
+use crate::events::Event;
+use crate::upgrades::AimAssist;
+use crate::world::{LaunchError, World};
+use glam::Vec2;
+
+/// Longest preview simulated, balls still moving after it are cut short
+const MAX_TICKS: usize = 6000;
+/// Ticks between two points of a path, bounces and hits are always recorded
+const SAMPLE_EVERY: usize = 25;
+
+/// Where a ball goes during the preview
+#[derive(Clone, Debug, PartialEq)]
+pub struct Path {
+    pub ball: BallId,
+    pub points: Vec<Vec2>,
+    /// Pocket the ball falls in at the end of the path
+    pub pocket: Option<usize>,
+    bounces: usize,
+    done: bool,
+}
+
+impl Path {
+    fn new(ball: BallId, start: Vec2) -> Self {
+        Self {
+            ball,
+            points: vec![start],
+            pocket: None,
+            bounces: 0,
+            done: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Preview {
+    /// Paths of the followed halves of the aimed ball, then of the balls they hit
+    pub paths: Vec<Path>,
+    /// First ball a followed half hits, and where they touch
+    pub first_hit: Option<(BallId, Vec2)>,
+}
+
+impl Preview {
+    /// Launch the `moves` of `player` on a copy of the world and follow the halves of `aimed` as
+    /// far as `assist` allows. The world itself is left untouched.
+    pub fn new(
+        world: &World,
+        player: PlayerId,
+        moves: &BTreeMap<BallId, Vec2>,
+        aimed: BallId,
+        assist: AimAssist,
+    ) -> Result<Self, LaunchError> {
+        let mut world = world.clone();
+        let halves = world.launch_round(player, moves)?;
+
+        let mut preview = Self::default();
+        let Some(halves) = halves.get(&aimed) else {
+            return Ok(preview);
+        };
+        for &half in halves.iter().take(assist.lines as usize) {
+            let start = world.ball(half).unwrap().borrow().position;
+            preview.paths.push(Path::new(half, start));
+        }
+        let followed_halves = preview.paths.len();
+
+        for tick in 1..=MAX_TICKS {
+            let (running, events) = world.tick();
+
+            for event in events {
+                preview.record(&world, event, followed_halves, assist);
+            }
+            if tick % SAMPLE_EVERY == 0 {
+                for path in preview.paths.iter_mut().filter(|path| !path.done) {
+                    Self::extend(&world, path);
+                }
+            }
+
+            if !running || preview.paths.iter().all(|path| path.done) {
+                break;
+            }
+        }
+
+        for path in preview.paths.iter_mut().filter(|path| !path.done) {
+            Self::extend(&world, path);
+        }
+        Ok(preview)
+    }
+
+    fn record(&mut self, world: &World, event: Event, followed_halves: usize, assist: AimAssist) {
+        match event {
+            Event::Bounce { ball, .. } => {
+                if let Some(path) = self.path(ball) {
+                    Self::bounce(world, path, assist.bounces);
+                }
+            }
+            Event::Collision { balls, point, .. } => {
+                for (ball, other) in [(balls[0], balls[1]), (balls[1], balls[0])] {
+                    let Some(index) = self.paths.iter().position(|path| path.ball == ball) else {
+                        continue;
+                    };
+                    Self::bounce(world, &mut self.paths[index], assist.bounces);
+
+                    let hit_by_half = index < followed_halves
+                        && self.paths[..followed_halves]
+                            .iter()
+                            .all(|path| path.ball != other);
+                    if !hit_by_half {
+                        continue;
+                    }
+                    self.first_hit.get_or_insert((other, point));
+                    // A splitter or a pocketed ball already left the world in this tick
+                    if assist.follow_hits
+                        && self.path(other).is_none()
+                        && let Some(other) = world.ball(other)
+                    {
+                        let other = other.borrow();
+                        self.paths.push(Path::new(other.id, other.position));
+                    }
+                }
+            }
+            Event::Pocketed { ball, pocket, .. } => {
+                if let Some(path) = self.path(ball) {
+                    path.points.push(world.table().pockets[pocket].position);
+                    path.pocket = Some(pocket);
+                    path.done = true;
+                }
+            }
+            Event::Merged {
+                balls, position, ..
+            } => {
+                for ball in balls {
+                    if let Some(path) = self.path(ball) {
+                        path.points.push(position);
+                        path.done = true;
+                    }
+                }
+            }
+            Event::Split { ball, position, .. } => {
+                if let Some(path) = self.path(ball) {
+                    path.points.push(position);
+                    path.done = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The path of a ball still being followed
+    fn path(&mut self, ball: BallId) -> Option<&mut Path> {
+        self.paths
+            .iter_mut()
+            .find(|path| path.ball == ball && !path.done)
+    }
+
+    /// The path turns here, it ends once it turned more than `max_bounces` times
+    fn bounce(world: &World, path: &mut Path, max_bounces: usize) {
+        if path.done {
+            return;
+        }
+        Self::extend(world, path);
+        path.bounces += 1;
+        path.done = path.bounces > max_bounces;
+    }
+
+    fn extend(world: &World, path: &mut Path) {
+        match world.ball(path.ball) {
+            Some(ball) => path.points.push(ball.borrow().position),
+            None => path.done = true,
+        }
+    }
+}
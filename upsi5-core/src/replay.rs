@@ -0,0 +1,321 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::entity::{BallId, PlayerId};
+use crate::events::Event;
+use crate::table::{Table, TableRegistry};
+use crate::upgrades::UpgradeLevels;
+use crate::world::{LaunchError, World};
+use glam::Vec2;
+
+const MAGIC: &[u8; 4] = b"SPRP";
+const VERSION: u8 = 5;
+/// Version 1 stored these four levels positionally
+const V1_LEVELS: [&str; 4] = ["max_speed", "profitability", "start_mass", "sliding"];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedRound {
+    pub round: usize,
+    /// Player whose slimes were launched, several players shooting together each get a record
+    pub player: PlayerId,
+    pub moves: BTreeMap<BallId, Vec2>,
+}
+
+/// Everything needed to re-simulate a run: the world is deterministic given its seed,
+/// its upgrade levels, its table and the shots played each round
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub levels: UpgradeLevels,
+    /// Only the id is stored, so the table must be one of the built-in ones
+    pub table: Table,
+    /// Players sharing the table, more than one in versus runs
+    pub players: u8,
+    pub rounds: Vec<RecordedRound>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The bytes aren't a replay this version can read
+    Format,
+    /// The recorded round doesn't match the round reached by the simulation
+    Desync {
+        expected: usize,
+        found: usize,
+    },
+    Launch(LaunchError),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Format => write!(f, "invalid replay data"),
+            Self::Desync { expected, found } => write!(
+                f,
+                "replay desynced, recorded round {expected} but the world is at round {found}"
+            ),
+            Self::Launch(e) => write!(f, "replay shot rejected: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<LaunchError> for ReplayError {
+    fn from(e: LaunchError) -> Self {
+        Self::Launch(e)
+    }
+}
+
+impl Replay {
+    pub fn new(seed: u64, levels: UpgradeLevels, table: &Table) -> Self {
+        Self::with_players(seed, levels, table, 1)
+    }
+
+    pub fn with_players(seed: u64, levels: UpgradeLevels, table: &Table, players: u8) -> Self {
+        Self {
+            seed,
+            levels,
+            table: table.clone(),
+            players,
+            rounds: Vec::new(),
+        }
+    }
+
+    /// The world as it was at the start of the run
+    pub fn world(&self) -> World {
+        World::with_players(self.seed, &self.levels, &self.table, self.players)
+    }
+
+    pub fn record(&mut self, round: usize, player: PlayerId, moves: &BTreeMap<BallId, Vec2>) {
+        self.rounds.push(RecordedRound {
+            round,
+            player,
+            moves: moves.clone(),
+        });
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        write_varint(&mut bytes, self.levels.iter().count() as u64);
+        for (id, level) in self.levels.iter() {
+            write_varint(&mut bytes, id.len() as u64);
+            bytes.extend_from_slice(id.as_bytes());
+            write_varint(&mut bytes, level as u64);
+        }
+        write_varint(&mut bytes, self.table.id.len() as u64);
+        bytes.extend_from_slice(self.table.id.as_bytes());
+        bytes.push(self.players);
+
+        write_varint(&mut bytes, self.rounds.len() as u64);
+        for round in &self.rounds {
+            write_varint(&mut bytes, round.round as u64);
+            bytes.push(round.player.0);
+            write_varint(&mut bytes, round.moves.len() as u64);
+            for (id, velocity) in &round.moves {
+                write_varint(&mut bytes, id.0);
+                bytes.extend_from_slice(&velocity.x.to_le_bytes());
+                bytes.extend_from_slice(&velocity.y.to_le_bytes());
+            }
+        }
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ReplayError> {
+        let mut reader = Reader { bytes };
+
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(ReplayError::Format);
+        }
+        let version = reader.take(1)?[0];
+
+        let seed = u64::from_le_bytes(reader.array()?);
+        let mut levels = UpgradeLevels::default();
+        let tables = TableRegistry::builtin();
+        // Older versions were all played alone on the first table
+        let mut table = tables.default_table();
+        let mut players = 1;
+
+        match version {
+            1 => {
+                for id in V1_LEVELS {
+                    levels.set(id, reader.varint()? as usize);
+                }
+            }
+            2..=VERSION => {
+                for _ in 0..reader.varint()? {
+                    let id = reader.string()?;
+                    levels.set(id, reader.varint()? as usize);
+                }
+                if version >= 3 {
+                    table = tables.get(reader.string()?).ok_or(ReplayError::Format)?;
+                }
+                if version >= 4 {
+                    players = reader.take(1)?[0];
+                }
+            }
+            _ => return Err(ReplayError::Format),
+        }
+
+        // Versions before 5 don't say who shot, which only a run played alone tells
+        if players == 0 || version < 5 && players > 1 {
+            return Err(ReplayError::Format);
+        }
+        let mut replay = Self::with_players(seed, levels, table, players);
+
+        for _ in 0..reader.varint()? {
+            let round = reader.varint()? as usize;
+            let player = if version >= 5 {
+                PlayerId(reader.take(1)?[0])
+            } else {
+                PlayerId(0)
+            };
+            if player.0 >= players {
+                return Err(ReplayError::Format);
+            }
+            let mut moves = BTreeMap::new();
+
+            for _ in 0..reader.varint()? {
+                let id = BallId(reader.varint()?);
+                let x = f32::from_le_bytes(reader.array()?);
+                let y = f32::from_le_bytes(reader.array()?);
+                moves.insert(id, Vec2::new(x, y));
+            }
+
+            replay.rounds.push(RecordedRound {
+                round,
+                player,
+                moves,
+            });
+        }
+
+        if reader.bytes.is_empty() {
+            Ok(replay)
+        } else {
+            Err(ReplayError::Format)
+        }
+    }
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], ReplayError> {
+        if self.bytes.len() < count {
+            return Err(ReplayError::Format);
+        }
+        let (taken, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ReplayError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn string(&mut self) -> Result<&'a str, ReplayError> {
+        let length = self.varint()? as usize;
+        std::str::from_utf8(self.take(length)?).map_err(|_| ReplayError::Format)
+    }
+
+    fn varint(&mut self) -> Result<u64, ReplayError> {
+        let mut value = 0;
+
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= u64::from(byte & 0x7F) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(ReplayError::Format)
+    }
+}
+
+/// Re-simulates a replay tick by tick, launching the recorded shots of a round once the table is
+/// at rest
+pub struct Playback {
+    pub world: World,
+    replay: Replay,
+    next_round: usize,
+    running: bool,
+}
+
+impl Playback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            world: replay.world(),
+            replay,
+            next_round: 0,
+            running: false,
+        }
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// A recorded round was launched and the table isn't at rest yet
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    pub fn is_finished(&self) -> bool {
+        !self.running && (self.world.is_game_over() || self.next_round >= self.replay.rounds.len())
+    }
+
+    /// Advance the simulation by one tick, the returned bool is true when a round just ended
+    pub fn tick(&mut self) -> Result<(bool, Vec<Event>), ReplayError> {
+        if self.is_finished() {
+            return Ok((false, Vec::new()));
+        }
+
+        if !self.running {
+            let recorded = &self.replay.rounds[self.next_round];
+            if recorded.round != self.world.round {
+                return Err(ReplayError::Desync {
+                    expected: recorded.round,
+                    found: self.world.round,
+                });
+            }
+            while let Some(recorded) = self.replay.rounds.get(self.next_round)
+                && recorded.round == self.world.round
+            {
+                self.world.launch_round(recorded.player, &recorded.moves)?;
+                self.next_round += 1;
+            }
+            self.running = true;
+        }
+
+        let (run, mut events) = self.world.tick();
+        if !run {
+            self.running = false;
+            events.extend(self.world.spawn_round());
+        }
+
+        Ok((!run, events))
+    }
+
+    /// Simulate the whole remaining replay and return the final world
+    pub fn run_to_end(mut self) -> Result<World, ReplayError> {
+        while !self.is_finished() {
+            self.tick()?;
+        }
+        Ok(self.world)
+    }
+}
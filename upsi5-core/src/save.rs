@@ -0,0 +1,131 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::achievements::AchievementProgress;
+use crate::base64;
+use crate::daily::DailyResult;
+use crate::upgrades::UpgradeLevels;
+
+pub const SAVE_VERSION: u64 = 6;
+
+/// Progression kept between sessions
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SaveData {
+    pub total_money: u64,
+    pub best_round: usize,
+    pub levels: UpgradeLevels,
+    /// The last daily challenge attempted
+    pub daily: Option<DailyResult>,
+    /// Replay of the last run played alone, as written by `Replay::to_bytes`, raced as a ghost
+    #[serde(with = "crate::base64::option")]
+    pub last_run: Option<Vec<u8>>,
+    pub achievements: AchievementProgress,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SaveError {
+    Format(String),
+    /// Written by a newer version of the game
+    UnsupportedVersion(u64),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Format(e) => write!(f, "invalid save data: {e}"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "save version {version} is newer than {SAVE_VERSION}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Format(e.to_string())
+    }
+}
+
+impl SaveData {
+    pub fn to_json(&self) -> String {
+        let mut value = serde_json::to_value(self).unwrap();
+        value["version"] = SAVE_VERSION.into();
+        value.to_string()
+    }
+
+    /// Parse a save of any known version, migrating it to the current one
+    pub fn from_json(json: &str) -> Result<Self, SaveError> {
+        let mut value: Value = serde_json::from_str(json)?;
+
+        let mut version = value["version"]
+            .as_u64()
+            .ok_or_else(|| SaveError::Format("missing version".to_owned()))?;
+
+        while version < SAVE_VERSION {
+            migrate(&mut value, version)?;
+            version += 1;
+        }
+
+        if version > SAVE_VERSION {
+            return Err(SaveError::UnsupportedVersion(version));
+        }
+
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+/// Upgrade a save from `version` to `version + 1`
+fn migrate(value: &mut Value, version: u64) -> Result<(), SaveError> {
+    match version {
+        // Version 1 had one field per upgrade, they now live in the levels map
+        1 => {
+            let fields = [
+                ("max_speed_level", "max_speed"),
+                ("start_mass_level", "start_mass"),
+                ("aim_assist_level", "aim_assist"),
+                ("profitability_level", "profitability"),
+                ("sliding_level", "sliding"),
+            ];
+
+            let object = value.as_object_mut().unwrap();
+            let mut levels = serde_json::Map::new();
+
+            for (field, id) in fields {
+                if let Some(level) = object.remove(field) {
+                    levels.insert(id.to_owned(), level);
+                }
+            }
+
+            object.insert("levels".to_owned(), Value::Object(levels));
+            Ok(())
+        }
+        // Version 2 predates the daily challenge
+        2 => {
+            value["daily"] = Value::Null;
+            Ok(())
+        }
+        // Version 3 didn't keep the last run
+        3 => {
+            value["last_run"] = Value::Null;
+            Ok(())
+        }
+        // Version 4 predates achievements
+        4 => {
+            value["achievements"] = serde_json::to_value(AchievementProgress::default())?;
+            Ok(())
+        }
+        // Version 5 wrote the last run as an array of numbers
+        5 => {
+            if !value["last_run"].is_null() {
+                let last_run: Vec<u8> = serde_json::from_value(value["last_run"].take())?;
+                value["last_run"] = base64::encode(&last_run).into();
+            }
+            Ok(())
+        }
+        _ => Err(SaveError::Format(format!("unknown save version {version}"))),
+    }
+}
@@ -0,0 +1,165 @@
+//! Combos made during a launched round. They only read the events of the ticks, and multiply
+//! the money of the coins picked up during the round once it's over.
+
+use std::collections::BTreeSet;
+
+use crate::entity::{BallId, BallType};
+use crate::events::Event;
+use crate::table::Table;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComboKind {
+    /// Another enemy fell in a pocket
+    Pocket,
+    /// Another ball picked up coins, usually the other half of a slime
+    Collector,
+    /// A ball pocketed or picked up a coin after bouncing off the border
+    BankShot,
+}
+
+/// How a round was scored, the bonus is the coin money times the multiplier minus one
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RoundScore {
+    /// Money of the coins picked up, before the multiplier
+    pub coins: u64,
+    /// Enemies pocketed
+    pub pockets: u32,
+    /// Balls that picked up coins
+    pub collectors: u32,
+    pub bank_shots: u32,
+    pub multiplier: u64,
+    pub bonus: u64,
+}
+
+impl RoundScore {
+    fn update_multiplier(&mut self) {
+        self.multiplier = 1
+            + u64::from(self.pockets.saturating_sub(1))
+            + u64::from(self.collectors.saturating_sub(1))
+            + u64::from(self.bank_shots);
+    }
+}
+
+/// Combos of the round being played
+#[derive(Clone, Debug, Default)]
+pub struct Combo {
+    score: RoundScore,
+    /// Money of each player when the round was launched
+    start_money: Vec<u64>,
+    collectors: BTreeSet<BallId>,
+    /// Balls that bounced off the border this round
+    bounced: BTreeSet<BallId>,
+    /// Balls that already made a bank shot, each one only counts once
+    banked: BTreeSet<BallId>,
+    /// A round was launched and isn't over
+    active: bool,
+}
+
+impl Combo {
+    /// A round was launched with the players having this money
+    pub fn start(&mut self, player_money: &[u64]) {
+        *self = Self {
+            score: RoundScore {
+                multiplier: 1,
+                ..RoundScore::default()
+            },
+            start_money: player_money.to_vec(),
+            active: true,
+            ..Self::default()
+        };
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// The round so far, the bonus is only known once it's over
+    pub fn score(&self) -> RoundScore {
+        self.score
+    }
+
+    /// Follow the events of a tick, and return a `Event::Combo` for each time they raised the
+    /// multiplier
+    pub fn record(&mut self, events: &[Event], table: &Table) -> Vec<Event> {
+        let mut combos = Vec::new();
+        if !self.active {
+            return combos;
+        }
+
+        for event in events {
+            let mut combo = |score: &mut RoundScore, kind, position| {
+                let multiplier = score.multiplier;
+                score.update_multiplier();
+                if score.multiplier > multiplier {
+                    combos.push(Event::Combo {
+                        kind,
+                        position,
+                        multiplier: score.multiplier,
+                    });
+                }
+            };
+
+            match *event {
+                Event::Bounce {
+                    ball,
+                    obstacle: None,
+                    ..
+                } => {
+                    self.bounced.insert(ball);
+                }
+                Event::Pocketed {
+                    ball,
+                    kind: BallType::Enemy(_),
+                    pocket,
+                } => {
+                    let position = table.pockets[pocket].position;
+                    self.score.pockets += 1;
+                    combo(&mut self.score, ComboKind::Pocket, position);
+                    if self.bank_shot(ball) {
+                        combo(&mut self.score, ComboKind::BankShot, position);
+                    }
+                }
+                Event::CoinCollected {
+                    ball,
+                    position,
+                    value,
+                } => {
+                    self.score.coins += value;
+                    if self.collectors.insert(ball) {
+                        self.score.collectors += 1;
+                        combo(&mut self.score, ComboKind::Collector, position);
+                    }
+                    if self.bank_shot(ball) {
+                        combo(&mut self.score, ComboKind::BankShot, position);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        combos
+    }
+
+    fn bank_shot(&mut self, ball: BallId) -> bool {
+        if self.bounced.contains(&ball) && self.banked.insert(ball) {
+            self.score.bank_shots += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// End the round, the bonus of each player is the money they earned during the round times
+    /// the multiplier minus one
+    pub fn finish(&mut self, player_money: &[u64]) -> (RoundScore, Vec<u64>) {
+        self.active = false;
+
+        let bonuses: Vec<u64> = player_money
+            .iter()
+            .zip(&self.start_money)
+            .map(|(money, start)| (money - start) * (self.score.multiplier - 1))
+            .collect();
+        self.score.bonus = bonuses.iter().sum();
+        (self.score, bonuses)
+    }
+}
@@ -0,0 +1,287 @@
+use std::{collections::BTreeMap, f32::consts::TAU, task::Poll};
+
+use crate::entity::{BallId, BallType, PlayerId};
+use crate::events::Event;
+use crate::world::{MAX_AIM, World};
+use glam::Vec2;
+
+/// Shots still moving after this many ticks are scored where they stand
+const MAX_TICKS: usize = 20_000;
+
+const COIN_SCORE: f32 = 1.;
+const ENEMY_SCORE: f32 = 3.;
+/// Score lost per unit of slime mass falling in a pocket
+const MASS_SCORE: f32 = 10.;
+const DEATH_SCORE: f32 = 1000.;
+
+/// What a shot did once every ball stopped
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Outcome {
+    pub coins: usize,
+    pub enemies_pocketed: usize,
+    pub mass_lost: f32,
+    /// The run ends after the shot, either on the table or at the next `spawn_round`
+    pub game_over: bool,
+}
+
+impl Outcome {
+    pub fn score(&self) -> f32 {
+        let death = if self.game_over { DEATH_SCORE } else { 0. };
+        self.coins as f32 * COIN_SCORE + self.enemies_pocketed as f32 * ENEMY_SCORE
+            - self.mass_lost * MASS_SCORE
+            - death
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Solution {
+    pub moves: BTreeMap<BallId, Vec2>,
+    pub outcome: Outcome,
+}
+
+/// Searches shots by simulating them on copies of the world. Each slime is tried at every
+/// direction and strength while the shots picked for the others stay put, `passes` times over.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Solver {
+    pub directions: usize,
+    /// Lengths of the aim, as dragged in the game
+    pub strengths: Vec<f32>,
+    pub passes: usize,
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Self {
+            directions: 16,
+            strengths: vec![MAX_AIM / 3., MAX_AIM * 2. / 3., MAX_AIM],
+            passes: 2,
+        }
+    }
+}
+
+impl Solver {
+    /// Few enough candidates for a hint searched over frames to come in quickly
+    pub fn hint() -> Self {
+        Self {
+            directions: 12,
+            strengths: vec![MAX_AIM / 2., MAX_AIM],
+            passes: 1,
+        }
+    }
+
+    /// Best shot found for the slimes of `player`, None when they have none. The slimes of the
+    /// other players stay put.
+    pub fn solve(&self, world: &World, player: PlayerId) -> Option<Solution> {
+        let Poll::Ready(best) = self.search(world, player).step(usize::MAX) else {
+            unreachable!("a search without tick budget always finishes");
+        };
+        best
+    }
+
+    /// The search done by `solve`, to play it a few ticks at a time
+    pub fn search(&self, world: &World, player: PlayerId) -> Search {
+        let slimes = world
+            .balls
+            .iter()
+            .map(|ball| ball.borrow())
+            .filter(|ball| ball.letypedelaboule == BallType::Player(player))
+            .map(|ball| ball.id)
+            .collect();
+
+        Search {
+            solver: self.clone(),
+            world: world.clone(),
+            player,
+            slimes,
+            pass: 0,
+            next_slime: 0,
+            candidates: Vec::new(),
+            shot: None,
+            moves: BTreeMap::new(),
+            slime_best: None,
+            best: None,
+        }
+    }
+
+    /// Every aim tried for a slime
+    pub fn aims(&self) -> impl Iterator<Item = Vec2> + '_ {
+        (0..self.directions).flat_map(move |direction| {
+            let angle = direction as f32 / self.directions as f32 * TAU;
+            self.strengths
+                .iter()
+                .map(move |&strength| Vec2::from_angle(angle) * strength)
+        })
+    }
+
+    /// Launch the moves of `player` on a copy of the world and let every ball come to rest
+    pub fn simulate(world: &World, player: PlayerId, moves: &BTreeMap<BallId, Vec2>) -> Outcome {
+        let mut budget = usize::MAX;
+        Shot::new(world, player, moves)
+            .play(&mut budget)
+            .expect("a shot without tick budget always finishes")
+    }
+
+    fn slime_mass(world: &World) -> f32 {
+        world
+            .balls
+            .iter()
+            .map(|ball| ball.borrow())
+            .filter(|ball| ball.letypedelaboule.is_player())
+            .map(|ball| ball.mass)
+            .sum()
+    }
+}
+
+/// A `Solver::solve` run that stops whenever it runs out of ticks and picks up where it was at the
+/// next `step`, so that a hint doesn't stall a frame
+#[derive(Clone)]
+pub struct Search {
+    solver: Solver,
+    world: World,
+    player: PlayerId,
+    slimes: Vec<BallId>,
+    pass: usize,
+    /// Index of the slime whose candidates come after the current ones
+    next_slime: usize,
+    /// Moves left to try for the current slime, the next one last
+    candidates: Vec<BTreeMap<BallId, Vec2>>,
+    shot: Option<(BTreeMap<BallId, Vec2>, Shot)>,
+    /// Moves picked for the slimes so far
+    moves: BTreeMap<BallId, Vec2>,
+    slime_best: Option<Solution>,
+    best: Option<Solution>,
+}
+
+impl Search {
+    /// Play at most `budget` ticks of the search, ready with the best shot once every candidate
+    /// was tried
+    pub fn step(&mut self, mut budget: usize) -> Poll<Option<Solution>> {
+        loop {
+            let Some((moves, shot)) = &mut self.shot else {
+                match self.candidates.pop() {
+                    Some(moves) => {
+                        let shot = Shot::new(&self.world, self.player, &moves);
+                        self.shot = Some((moves, shot));
+                    }
+                    None => {
+                        if let Some(solution) = self.slime_best.take() {
+                            self.moves = solution.moves.clone();
+                            self.best = Some(solution);
+                        }
+                        if !self.next_candidates() {
+                            return Poll::Ready(self.best.take());
+                        }
+                    }
+                }
+                continue;
+            };
+
+            let Some(outcome) = shot.play(&mut budget) else {
+                return Poll::Pending;
+            };
+            if self
+                .slime_best
+                .as_ref()
+                .is_none_or(|best| outcome.score() > best.outcome.score())
+            {
+                self.slime_best = Some(Solution {
+                    moves: std::mem::take(moves),
+                    outcome,
+                });
+            }
+            self.shot = None;
+        }
+    }
+
+    /// Queue the moves to try for the next slime, false once every pass is over
+    fn next_candidates(&mut self) -> bool {
+        if self.next_slime == self.slimes.len() {
+            self.next_slime = 0;
+            self.pass += 1;
+        }
+        if self.slimes.is_empty() || self.pass == self.solver.passes {
+            return false;
+        }
+        let slime = self.slimes[self.next_slime];
+        self.next_slime += 1;
+
+        let mut candidates = self.moves.clone();
+        candidates.remove(&slime);
+        // Leaving the slime alone is a candidate too, as long as another one is shot
+        let alone = (!candidates.is_empty()).then(|| candidates.clone());
+        let aims = self.solver.aims().map(|aim| {
+            let mut moves = candidates.clone();
+            moves.insert(slime, aim);
+            moves
+        });
+        self.candidates = alone.into_iter().chain(aims).collect();
+        self.candidates.reverse();
+        true
+    }
+}
+
+/// Moves being played on a copy of the world
+#[derive(Clone)]
+struct Shot {
+    world: World,
+    launched: bool,
+    mass_before: f32,
+    outcome: Outcome,
+    ticks: usize,
+}
+
+impl Shot {
+    fn new(world: &World, player: PlayerId, moves: &BTreeMap<BallId, Vec2>) -> Self {
+        let mut world = world.clone();
+        let mass_before = Solver::slime_mass(&world);
+        let launched = world.launch_round(player, moves).is_ok();
+
+        Self {
+            world,
+            launched,
+            mass_before,
+            outcome: Outcome {
+                game_over: !launched,
+                ..Outcome::default()
+            },
+            ticks: 0,
+        }
+    }
+
+    /// Play ticks out of `budget` until every ball stopped, None when the budget ran out first
+    fn play(&mut self, budget: &mut usize) -> Option<Outcome> {
+        if !self.launched {
+            return Some(self.outcome);
+        }
+
+        while self.ticks < MAX_TICKS {
+            if *budget == 0 {
+                return None;
+            }
+            *budget -= 1;
+            self.ticks += 1;
+
+            let (running, events) = self.world.tick();
+            for event in events {
+                match event {
+                    Event::CoinCollected { .. } => self.outcome.coins += 1,
+                    Event::Pocketed {
+                        kind: BallType::Enemy(_),
+                        ..
+                    } => self.outcome.enemies_pocketed += 1,
+                    _ => {}
+                }
+            }
+            if !running {
+                break;
+            }
+        }
+
+        self.outcome.mass_lost = self.mass_before - Solver::slime_mass(&self.world);
+        if !self.world.is_game_over() {
+            self.world.spawn_round();
+        }
+        self.outcome.game_over = self.world.is_game_over();
+        Some(self.outcome)
+    }
+}
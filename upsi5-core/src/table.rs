@@ -0,0 +1,168 @@
+use std::{fmt, sync::OnceLock};
+
+use glam::Vec2;
+use serde::Deserialize;
+
+use crate::entity::Archetype;
+use crate::obstacles::Obstacle;
+
+const BUILTIN_TABLES: &str = include_str!("../data/tables.json");
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub struct Pocket {
+    pub position: Vec2,
+    pub radius: f32,
+}
+
+/// A ball placed on the table when a run starts
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Spot {
+    Slime {
+        position: Vec2,
+    },
+    Enemy {
+        position: Vec2,
+        #[serde(default)]
+        archetype: Archetype,
+        #[serde(default = "default_timer")]
+        timer: usize,
+    },
+}
+
+const fn default_timer() -> usize {
+    5
+}
+
+/// Shape of the playing area, positions are relative to its bottom left corner
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Table {
+    pub id: String,
+    pub name: String,
+    pub dimensions: Vec2,
+    /// Width of the cushion drawn around the playing area
+    pub border: f32,
+    /// Name of the texture drawn under the table, border included
+    pub background: String,
+    pub pockets: Vec<Pocket>,
+    pub formation: Vec<Spot>,
+}
+
+impl Table {
+    /// Distance from the cushions kept free when spawning, so nothing appears in a pocket
+    pub fn spawn_inset(&self) -> f32 {
+        self.pockets
+            .iter()
+            .fold(0f32, |inset, pocket| inset.max(pocket.radius))
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.cmpge(Vec2::ZERO).all() && point.cmple(self.dimensions).all()
+    }
+
+    /// Whether the obstacle lies on the table without covering a pocket
+    pub fn holds(&self, obstacle: &Obstacle) -> bool {
+        obstacle
+            .anchors()
+            .into_iter()
+            .all(|anchor| self.contains(anchor))
+            && !self
+                .pockets
+                .iter()
+                .any(|pocket| obstacle.overlaps(pocket.position, pocket.radius))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TableError(pub String);
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid table: {}", self.0)
+    }
+}
+
+impl std::error::Error for TableError {}
+
+pub struct TableRegistry {
+    tables: Vec<Table>,
+}
+
+impl TableRegistry {
+    /// The tables embedded in the game
+    pub fn builtin() -> &'static Self {
+        static REGISTRY: OnceLock<TableRegistry> = OnceLock::new();
+
+        REGISTRY.get_or_init(|| Self::from_json(BUILTIN_TABLES).unwrap())
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, TableError> {
+        let tables: Vec<Table> =
+            serde_json::from_str(json).map_err(|e| TableError(e.to_string()))?;
+
+        if tables.is_empty() {
+            return Err(TableError("no table".to_owned()));
+        }
+
+        for (index, table) in tables.iter().enumerate() {
+            if tables[..index].iter().any(|t| t.id == table.id) {
+                return Err(TableError(format!("duplicate id {}", table.id)));
+            }
+            if table.dimensions.min_element() <= 0. {
+                return Err(TableError(format!("{} has no area", table.id)));
+            }
+            if !table
+                .pockets
+                .iter()
+                .all(|pocket| pocket.radius > 0. && table.contains(pocket.position))
+            {
+                return Err(TableError(format!(
+                    "{} has a pocket off the table",
+                    table.id
+                )));
+            }
+
+            let mut slimes = 0;
+            for spot in &table.formation {
+                let position = match *spot {
+                    Spot::Slime { position } => {
+                        slimes += 1;
+                        position
+                    }
+                    Spot::Enemy {
+                        position, timer, ..
+                    } => {
+                        if timer == 0 {
+                            return Err(TableError(format!(
+                                "{} has an enemy without timer",
+                                table.id
+                            )));
+                        }
+                        position
+                    }
+                };
+                if !table.contains(position) {
+                    return Err(TableError(format!("{} has a ball off the table", table.id)));
+                }
+            }
+            if slimes == 0 {
+                return Err(TableError(format!("{} starts without slime", table.id)));
+            }
+        }
+
+        Ok(Self { tables })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Table> {
+        self.tables.iter()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Table> {
+        self.tables.iter().find(|table| table.id == id)
+    }
+
+    /// The first table of the catalogue, used when nothing else was picked
+    pub fn default_table(&self) -> &Table {
+        &self.tables[0]
+    }
+}
@@ -0,0 +1,180 @@
+use std::{collections::BTreeMap, fmt, sync::OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+const BUILTIN_UPGRADES: &str = include_str!("../data/upgrades.json");
+
+/// What the aim preview shows: the path of `lines` halves of the aimed slime, up to their
+/// `bounces + 1`th bounce, and with `follow_hits` the paths of the balls they hit
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+pub struct AimAssist {
+    pub lines: u32,
+    pub bounces: usize,
+    #[serde(default)]
+    pub follow_hits: bool,
+}
+
+/// What an upgrade changes, with one value per level starting at level 0
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "kind", content = "values", rename_all = "snake_case")]
+pub enum Effect {
+    MaxSpeed(Vec<f32>),
+    StartMass(Vec<f32>),
+    CoinMultiplier(Vec<u64>),
+    Friction(Vec<f32>),
+    AimAssist(Vec<AimAssist>),
+    /// Shots that can be taken back during a run
+    Undos(Vec<u32>),
+}
+
+impl Effect {
+    fn len(&self) -> usize {
+        match self {
+            Self::MaxSpeed(values) | Self::StartMass(values) | Self::Friction(values) => {
+                values.len()
+            }
+            Self::CoinMultiplier(values) => values.len(),
+            Self::AimAssist(values) => values.len(),
+            Self::Undos(values) => values.len(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Upgrade {
+    pub id: String,
+    /// Name of the shop icon texture
+    pub icon: String,
+    pub max_level: usize,
+    /// Price of each level, `prices[0]` buys level 1
+    pub prices: Vec<u64>,
+    pub effect: Effect,
+}
+
+impl Upgrade {
+    /// Price of the next level, None once maxed out
+    pub fn price(&self, level: usize) -> Option<u64> {
+        self.prices.get(level).copied()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RegistryError(pub String);
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid upgrade registry: {}", self.0)
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+pub struct UpgradeRegistry {
+    upgrades: Vec<Upgrade>,
+}
+
+impl UpgradeRegistry {
+    /// The catalogue embedded in the game
+    pub fn builtin() -> &'static Self {
+        static REGISTRY: OnceLock<UpgradeRegistry> = OnceLock::new();
+
+        REGISTRY.get_or_init(|| Self::from_json(BUILTIN_UPGRADES).unwrap())
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, RegistryError> {
+        let upgrades: Vec<Upgrade> =
+            serde_json::from_str(json).map_err(|e| RegistryError(e.to_string()))?;
+
+        for (index, upgrade) in upgrades.iter().enumerate() {
+            if upgrades[..index].iter().any(|u| u.id == upgrade.id) {
+                return Err(RegistryError(format!("duplicate id {}", upgrade.id)));
+            }
+            if upgrade.prices.len() != upgrade.max_level {
+                return Err(RegistryError(format!(
+                    "{} needs one price per level",
+                    upgrade.id
+                )));
+            }
+            if upgrade.effect.len() != upgrade.max_level + 1 {
+                return Err(RegistryError(format!(
+                    "{} needs one effect value per level, level 0 included",
+                    upgrade.id
+                )));
+            }
+        }
+
+        Ok(Self { upgrades })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Upgrade> {
+        self.upgrades.iter()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Upgrade> {
+        self.upgrades.iter().find(|upgrade| upgrade.id == id)
+    }
+
+    /// Apply every upgrade at its current level, when several upgrades have the same kind of
+    /// effect the last one in the catalogue wins
+    pub fn stats(&self, levels: &UpgradeLevels) -> Stats {
+        let mut stats = Stats::default();
+
+        for upgrade in &self.upgrades {
+            let level = levels.get(&upgrade.id).min(upgrade.max_level);
+
+            match &upgrade.effect {
+                Effect::MaxSpeed(values) => stats.max_speed = values[level],
+                Effect::StartMass(values) => stats.start_mass = values[level],
+                Effect::CoinMultiplier(values) => stats.coin_multiplier = values[level],
+                Effect::Friction(values) => stats.friction = values[level],
+                Effect::AimAssist(values) => stats.aim_assist = values[level],
+                Effect::Undos(values) => stats.undos = values[level],
+            }
+        }
+
+        stats
+    }
+}
+
+/// Level reached in each upgrade, by upgrade id
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct UpgradeLevels(BTreeMap<String, usize>);
+
+impl UpgradeLevels {
+    pub fn get(&self, id: &str) -> usize {
+        self.0.get(id).copied().unwrap_or(0)
+    }
+
+    pub fn set(&mut self, id: &str, level: usize) {
+        self.0.insert(id.to_owned(), level);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.0.iter().map(|(id, &level)| (id.as_str(), level))
+    }
+}
+
+/// Values the simulation reads, resulting from the upgrade levels
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub max_speed: f32,
+    pub start_mass: f32,
+    pub coin_multiplier: u64,
+    pub friction: f32,
+    pub aim_assist: AimAssist,
+    pub undos: u32,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            max_speed: 0.01,
+            start_mass: 0.3,
+            coin_multiplier: 1,
+            friction: 0.9994,
+            aim_assist: AimAssist::default(),
+            undos: 1,
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use std::{fmt, sync::OnceLock};
+
+use glam::Vec2;
+use serde::Deserialize;
+
+use crate::entity::Archetype;
+use crate::items::ItemKind;
+use crate::obstacles::Obstacle;
+
+const BUILTIN_WAVES: &str = include_str!("../data/waves.json");
+
+/// A group of identical enemies joining the table
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub struct EnemySpawn {
+    #[serde(default)]
+    pub kind: Archetype,
+    pub count: usize,
+    /// Rounds left before the enemies end the run
+    pub timer: usize,
+}
+
+/// Inclusive range of coins spawned
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub struct CoinRange {
+    pub min: usize,
+    pub max: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WaveEvent {
+    /// Extra coins on top of the usual ones
+    CoinRain { count: usize },
+    /// Enemy timers don't go down this round
+    Calm,
+    /// Adds an obstacle to the table for the rest of the run, its positions are fractions of the
+    /// table dimensions
+    Obstacle { obstacle: Obstacle },
+    /// Drops a power-up on the table
+    PowerUp { item: ItemKind },
+}
+
+/// What happens at the end of a round
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Wave {
+    #[serde(default)]
+    pub enemies: Vec<EnemySpawn>,
+    pub coins: CoinRange,
+    #[serde(default)]
+    pub events: Vec<WaveEvent>,
+}
+
+impl Wave {
+    pub fn is_calm(&self) -> bool {
+        self.events.contains(&WaveEvent::Calm)
+    }
+
+    pub fn enemy_count(&self) -> usize {
+        self.enemies.iter().map(|spawn| spawn.count).sum()
+    }
+}
+
+/// Generates the rounds coming after the authored ones, getting harder as they go
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct Endless {
+    pub first_enemies: usize,
+    /// One more enemy every this many rounds
+    pub enemies_every: usize,
+    pub max_enemies: usize,
+    pub timer: usize,
+    /// Timers lose one round every this many rounds
+    pub timer_decay_every: usize,
+    pub min_timer: usize,
+    /// One enemy of each wave is taken from this list in turn, the others are basic
+    #[serde(default)]
+    pub archetypes: Vec<Archetype>,
+    pub coins: CoinRange,
+    /// A coin rain every this many rounds, 0 for never
+    pub coin_rain_every: usize,
+    pub coin_rain: usize,
+    /// A power-up every this many rounds, 0 for never
+    #[serde(default)]
+    pub power_up_every: usize,
+    /// Power-ups dropped in turn
+    #[serde(default)]
+    pub power_ups: Vec<ItemKind>,
+}
+
+impl Endless {
+    /// The wave `depth` rounds after the last authored one
+    pub fn wave(&self, depth: usize) -> Wave {
+        let count = (self.first_enemies + depth / self.enemies_every).min(self.max_enemies);
+        let timer = self
+            .timer
+            .saturating_sub(depth / self.timer_decay_every)
+            .max(self.min_timer);
+
+        let mut events = Vec::new();
+        if (depth + 1).is_multiple_of(self.coin_rain_every) {
+            events.push(WaveEvent::CoinRain {
+                count: self.coin_rain,
+            });
+        }
+        if !self.power_ups.is_empty() && (depth + 1).is_multiple_of(self.power_up_every) {
+            let drop = (depth + 1) / self.power_up_every - 1;
+            events.push(WaveEvent::PowerUp {
+                item: self.power_ups[drop % self.power_ups.len()],
+            });
+        }
+
+        let mut enemies = vec![EnemySpawn {
+            kind: Archetype::Basic,
+            count,
+            timer,
+        }];
+        if count > 0 && !self.archetypes.is_empty() {
+            enemies[0].count -= 1;
+            enemies.push(EnemySpawn {
+                kind: self.archetypes[depth % self.archetypes.len()],
+                count: 1,
+                timer,
+            });
+        }
+
+        Wave {
+            enemies,
+            coins: self.coins,
+            events,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct WaveError(pub String);
+
+impl fmt::Display for WaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid wave script: {}", self.0)
+    }
+}
+
+impl std::error::Error for WaveError {}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct WaveScript {
+    rounds: Vec<Wave>,
+    endless: Endless,
+}
+
+impl WaveScript {
+    /// The waves embedded in the game
+    pub fn builtin() -> &'static Self {
+        static SCRIPT: OnceLock<WaveScript> = OnceLock::new();
+
+        SCRIPT.get_or_init(|| Self::from_json(BUILTIN_WAVES).unwrap())
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, WaveError> {
+        let script: Self = serde_json::from_str(json).map_err(|e| WaveError(e.to_string()))?;
+
+        for (round, wave) in script.rounds.iter().enumerate() {
+            if wave.coins.min > wave.coins.max {
+                return Err(WaveError(format!("round {round} has an empty coin range")));
+            }
+            if wave.enemies.iter().any(|spawn| spawn.timer == 0) {
+                return Err(WaveError(format!(
+                    "round {round} has an enemy without timer"
+                )));
+            }
+            if wave.events.iter().any(|event| match event {
+                WaveEvent::Obstacle { obstacle } => obstacle
+                    .anchors()
+                    .iter()
+                    .any(|anchor| !(anchor.cmpge(Vec2::ZERO) & anchor.cmple(Vec2::ONE)).all()),
+                _ => false,
+            }) {
+                return Err(WaveError(format!(
+                    "round {round} has an obstacle off the table"
+                )));
+            }
+            if wave.events.contains(&WaveEvent::PowerUp {
+                item: ItemKind::Coin,
+            }) {
+                return Err(WaveError(format!(
+                    "round {round} drops a coin as a power-up"
+                )));
+            }
+        }
+
+        let endless = &script.endless;
+        if endless.coins.min > endless.coins.max {
+            return Err(WaveError("endless has an empty coin range".to_owned()));
+        }
+        if endless.enemies_every == 0 || endless.timer_decay_every == 0 {
+            return Err(WaveError(
+                "endless progression must be at least one round".to_owned(),
+            ));
+        }
+        if endless.power_ups.contains(&ItemKind::Coin) {
+            return Err(WaveError("endless drops a coin as a power-up".to_owned()));
+        }
+        if endless.min_timer == 0 || endless.min_timer > endless.timer {
+            return Err(WaveError(
+                "endless timers must be between 1 and timer".to_owned(),
+            ));
+        }
+
+        Ok(script)
+    }
+
+    /// Number of hand written rounds before the endless generator takes over
+    pub fn authored_rounds(&self) -> usize {
+        self.rounds.len()
+    }
+
+    pub fn wave(&self, round: usize) -> Wave {
+        match self.rounds.get(round) {
+            Some(wave) => wave.clone(),
+            None => self.endless.wave(round - self.rounds.len()),
+        }
+    }
+}
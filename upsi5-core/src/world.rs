@@ -0,0 +1,1014 @@
+use std::{cell::RefCell, collections::BTreeMap, fmt};
+
+use crate::broad_phase::Grid;
+use crate::entity::{
+    self, Archetype, Ball, BallId, BallIdAllocator, BallType, EnemyData, PlayerId,
+};
+use crate::events::Event;
+use crate::items::{self, Item, ItemKind, PowerUps};
+use crate::obstacles::Obstacle;
+use crate::physics;
+use crate::rng::Rng;
+use crate::scoring::{Combo, RoundScore};
+use crate::table::{Spot, Table};
+use crate::upgrades::{Stats, UpgradeLevels, UpgradeRegistry};
+use crate::waves::{Wave, WaveEvent, WaveScript};
+use glam::Vec2;
+
+pub const COIN_RADIUS: f32 = 0.01;
+const COIN_PRICE: u64 = 100;
+const MAX_POS_TRY: i32 = 100;
+const GRID_CELL_SIZE: f32 = 0.08;
+const MIN_GRID_CELL_SIZE: f32 = 0.01;
+
+/// Longest aim a player can drag, the launch speed is the aim times the max speed upgrade
+pub const MAX_AIM: f32 = 0.15;
+/// Aims clamped to `MAX_AIM` can come out a rounding error longer
+const AIM_TOLERANCE: f32 = 1e-6;
+
+pub const PLAYER_START_SIZE: f32 = 0.05;
+/// Distance between the starting slimes of two players sharing a table
+const PLAYER_SPACING: f32 = 0.3;
+pub const ENEMY_BALL_SIZE: f32 = 0.035;
+const ENEMY_MASS: f32 = 0.15;
+pub const ANCHOR_BALL_SIZE: f32 = 0.045;
+const ANCHOR_MASS: f32 = 0.9;
+/// Anchors lose speed this many times faster than other balls
+const ANCHOR_DRAG: i32 = 4;
+/// Speed given to each half of a splitter, relative to the splitter speed
+const SPLIT_SPEED: f32 = 0.5;
+const BOMB_BLAST_RADIUS: f32 = 0.3;
+/// Speed given to a basic enemy right next to an exploding bomb
+const BOMB_PUSH: f32 = 0.015;
+/// Angle between the pieces of a triple split
+const TRIPLE_SPLIT_ANGLE: f32 = std::f32::consts::TAU / 3.;
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord)]
+pub enum Sounds {
+    SlimeSlime,
+    Coin,
+    SlimeEnemy,
+    EnemyEnemy,
+    Border,
+    Falling,
+}
+
+/// What a contact changed on the table besides bouncing the balls
+pub enum Collision {
+    /// Two slimes became this ball
+    Merge(Ball),
+    /// The ball at this index broke into these two
+    Split(usize, [Ball; 2]),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum LaunchError {
+    UnknownBall(BallId),
+    /// Only slimes can be launched, enemies are moved by the slimes hitting them
+    NotASlime(BallId),
+    /// The slime belongs to another player than the one shooting
+    NotYours(BallId),
+    /// A slime launched without aim would split in two halves on the same spot
+    ZeroAim(BallId),
+    /// The aim is longer than `MAX_AIM`, or isn't a number
+    AimTooLong(BallId),
+}
+
+impl fmt::Display for LaunchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownBall(id) => write!(f, "no ball with id {}", id.0),
+            Self::NotASlime(id) => write!(f, "ball {} isn't a slime", id.0),
+            Self::NotYours(id) => write!(f, "slime {} belongs to another player", id.0),
+            Self::ZeroAim(id) => write!(f, "slime {} has no aim", id.0),
+            Self::AimTooLong(id) => write!(f, "slime {} is aimed further than {MAX_AIM}", id.0),
+        }
+    }
+}
+
+impl std::error::Error for LaunchError {}
+
+/// The table as it was before a shot, to take the shot back
+#[derive(Clone)]
+pub struct Snapshot {
+    balls: Vec<Ball>,
+    items: Vec<Item>,
+    power_ups: Vec<PowerUps>,
+    frozen: bool,
+    magnets: Vec<BallId>,
+    money: u64,
+    player_money: Vec<u64>,
+    round: usize,
+    game_over: bool,
+    obstacles: Vec<Obstacle>,
+    // Rewound too so that playing the next shots again gives the same spawns and ids
+    rng: Rng,
+    ball_ids: BallIdAllocator,
+}
+
+#[derive(Clone)]
+pub struct World {
+    pub balls: Vec<RefCell<entity::Ball>>,
+    pub money: u64,
+    /// Money earned by each player, `money` is their sum
+    pub player_money: Vec<u64>,
+    pub round: usize,
+    /// Coins and power-ups lying on the table
+    pub items: Vec<Item>,
+    /// Power-ups waiting to be used by each player
+    pub power_ups: Vec<PowerUps>,
+    /// Enemy timers skip the end of this round
+    pub frozen: bool,
+    pub obstacles: Vec<Obstacle>,
+    /// Slimes pulling the coins around them until the end of the round
+    magnets: Vec<BallId>,
+    game_over: bool,
+    rng: Rng,
+    ball_ids: BallIdAllocator,
+    stats: Stats,
+    combo: Combo,
+    ball_grid: Grid,
+    item_grid: Grid,
+    hole_grid: Grid,
+    table: Table,
+}
+
+impl World {
+    pub fn new(seed: u64, levels: &UpgradeLevels, table: &Table) -> Self {
+        Self::with_players(seed, levels, table, 1)
+    }
+
+    /// A world shared by several players, each slime of the formation is replaced by one slime
+    /// per player, side by side
+    pub fn with_players(seed: u64, levels: &UpgradeLevels, table: &Table, players: u8) -> Self {
+        let stats = UpgradeRegistry::builtin().stats(levels);
+
+        let mut new_world = World {
+            balls: Vec::new(),
+            money: 0,
+            player_money: vec![0; players as usize],
+            round: 0,
+            game_over: false,
+            rng: Rng::new(seed),
+            ball_ids: BallIdAllocator::default(),
+            stats,
+            combo: Combo::default(),
+            items: Vec::new(),
+            power_ups: vec![PowerUps::default(); players as usize],
+            frozen: false,
+            obstacles: Vec::new(),
+            magnets: Vec::new(),
+            ball_grid: Grid::new(table.dimensions, GRID_CELL_SIZE),
+            item_grid: Grid::new(table.dimensions, GRID_CELL_SIZE),
+            hole_grid: Grid::new(table.dimensions, GRID_CELL_SIZE),
+            table: table.clone(),
+        };
+        for (index, pocket) in table.pockets.iter().enumerate() {
+            new_world.hole_grid.insert(
+                index,
+                pocket.position - pocket.radius,
+                pocket.position + pocket.radius,
+            );
+        }
+
+        for spot in &table.formation {
+            match *spot {
+                Spot::Slime { position } => {
+                    for owner in 0..players {
+                        let offset = owner as f32 - (players - 1) as f32 / 2.;
+                        new_world.add_ball(
+                            position + Vec2::X * offset * PLAYER_SPACING,
+                            PLAYER_START_SIZE,
+                            stats.start_mass,
+                            stats.friction,
+                            BallType::Player(PlayerId(owner)),
+                        );
+                    }
+                }
+                Spot::Enemy {
+                    position,
+                    archetype,
+                    timer,
+                } => {
+                    let (radius, mass, friction) = Self::enemy_body(archetype, stats.friction);
+                    new_world.add_ball(
+                        position,
+                        radius,
+                        mass,
+                        friction,
+                        entity::BallType::Enemy(EnemyData::new(archetype, timer)),
+                    );
+                }
+            }
+        }
+        new_world
+    }
+
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    pub fn players(&self) -> u8 {
+        self.player_money.len() as u8
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            balls: self.balls.iter().map(|ball| *ball.borrow()).collect(),
+            items: self.items.clone(),
+            power_ups: self.power_ups.clone(),
+            frozen: self.frozen,
+            magnets: self.magnets.clone(),
+            money: self.money,
+            player_money: self.player_money.clone(),
+            round: self.round,
+            game_over: self.game_over,
+            obstacles: self.obstacles.clone(),
+            rng: self.rng.clone(),
+            ball_ids: self.ball_ids.clone(),
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: Snapshot) {
+        self.balls = snapshot.balls.into_iter().map(RefCell::new).collect();
+        self.items = snapshot.items;
+        self.power_ups = snapshot.power_ups;
+        self.frozen = snapshot.frozen;
+        self.magnets = snapshot.magnets;
+        self.money = snapshot.money;
+        self.player_money = snapshot.player_money;
+        self.round = snapshot.round;
+        self.game_over = snapshot.game_over;
+        self.obstacles = snapshot.obstacles;
+        self.rng = snapshot.rng;
+        self.ball_ids = snapshot.ball_ids;
+    }
+
+    /// FNV-1a hash of the simulated state, two worlds that went through the same rounds have the
+    /// same checksum unless their simulations diverged
+    pub fn checksum(&self) -> u64 {
+        let mut hash: u64 = 0xCBF2_9CE4_8422_2325;
+        let mut feed = |value: u64| {
+            for byte in value.to_le_bytes() {
+                hash = (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01B3);
+            }
+        };
+
+        feed(self.round as u64);
+        feed(u64::from(self.game_over));
+        feed(self.money);
+        self.player_money.iter().for_each(|&money| feed(money));
+        // Covers the state of the generator without exposing it
+        feed(self.rng.clone().next_u64());
+
+        for ball in &self.balls {
+            let ball = ball.borrow();
+            feed(ball.id.0);
+            for value in [ball.position, ball.speed] {
+                feed(u64::from(value.x.to_bits()));
+                feed(u64::from(value.y.to_bits()));
+            }
+            feed(u64::from(ball.mass.to_bits()));
+            feed(u64::from(ball.radius.to_bits()));
+            match ball.letypedelaboule {
+                BallType::Player(owner) => feed(u64::from(owner.0)),
+                BallType::Enemy(enemy) => {
+                    feed(enemy.archetype as u64 + 0x100);
+                    feed(enemy.timer as u64);
+                    feed(u64::from(enemy.shield));
+                }
+            }
+        }
+
+        for item in &self.items {
+            feed(item.kind as u64);
+            feed(u64::from(item.position.x.to_bits()));
+            feed(u64::from(item.position.y.to_bits()));
+        }
+        for power_ups in &self.power_ups {
+            feed(u64::from(power_ups.doubled_coins));
+            feed(power_ups.triple_splits.len() as u64);
+            power_ups
+                .triple_splits
+                .iter()
+                .for_each(|slime| feed(slime.0));
+        }
+        feed(u64::from(self.frozen));
+        self.magnets.iter().for_each(|magnet| feed(magnet.0));
+
+        hash
+    }
+
+    /// Coins then power-ups, each one at a free position
+    fn spawn_items(&mut self, wave: &Wave) {
+        let mut coin_number = self
+            .rng
+            .rand_range(wave.coins.min as f64, (wave.coins.max + 1) as f64)
+            as usize;
+        for event in &wave.events {
+            if let WaveEvent::CoinRain { count } = event {
+                coin_number += count;
+            }
+        }
+
+        for _ in 0..coin_number {
+            let coin_pos = self.get_free_pos(COIN_RADIUS);
+            self.items.push(Item::coin(coin_pos));
+        }
+
+        for event in &wave.events {
+            if let WaveEvent::PowerUp { item } = *event {
+                let position = self.get_free_pos(item.radius());
+                self.items.push(Item {
+                    kind: item,
+                    position,
+                });
+            }
+        }
+    }
+
+    fn spawn_enemies(&mut self, wave: &Wave) {
+        for spawn in &wave.enemies {
+            for _ in 0..spawn.count {
+                let (radius, mass, new_friction_coeff) =
+                    Self::enemy_body(spawn.kind, self.stats.friction);
+                let new_pos = self.get_free_pos(radius);
+
+                self.add_ball(
+                    new_pos,
+                    radius,
+                    mass,
+                    new_friction_coeff,
+                    BallType::Enemy(EnemyData::new(spawn.kind, spawn.timer)),
+                );
+            }
+        }
+    }
+
+    fn get_free_pos(&mut self, radius: f32) -> Vec2 {
+        let inset = self.table.spawn_inset();
+        let x1 = inset + radius;
+        let x2 = self.table.dimensions.x - inset - radius;
+        let y1 = inset + radius;
+        let y2 = self.table.dimensions.y - inset - radius;
+
+        let mut pos_not_ok = true;
+        let mut count = 0;
+        let mut new_pos = Vec2::ZERO;
+
+        while pos_not_ok {
+            if count > MAX_POS_TRY {
+                self.game_over = true;
+                break;
+            }
+            new_pos = Vec2::new(
+                self.rng.rand_range(x1 as f64, x2 as f64) as f32,
+                self.rng.rand_range(y1 as f64, y2 as f64) as f32,
+            );
+            pos_not_ok = false;
+            for index in 0..self.balls.len() {
+                let ball = self.balls[index].borrow();
+
+                let dist = ball.position - new_pos;
+
+                if ball.radius + radius - dist.length() > 0. {
+                    pos_not_ok = true;
+                }
+            }
+            if self
+                .obstacles
+                .iter()
+                .any(|obstacle| obstacle.overlaps(new_pos, radius))
+                || self
+                    .items
+                    .iter()
+                    .any(|item| item.position.distance(new_pos) < item.radius() + radius)
+            {
+                pos_not_ok = true;
+            }
+            count += 1;
+        }
+        new_pos
+    }
+
+    /// Whether the obstacle doesn't cover any ball or item
+    fn is_clear(&self, obstacle: &Obstacle) -> bool {
+        let balls = self.balls.iter().map(|ball| {
+            let ball = ball.borrow();
+            (ball.position, ball.radius)
+        });
+        let items = self.items.iter().map(|item| (item.position, item.radius()));
+
+        !balls
+            .chain(items)
+            .any(|(position, radius)| obstacle.overlaps(position, radius))
+    }
+
+    pub fn spawn_round(&mut self) -> Vec<Event> {
+        let mut events = Vec::new();
+        let wave = WaveScript::builtin().wave(self.round);
+
+        // Wave obstacles covering a pocket are dropped, like the ones landing on a ball or an item
+        // which nothing would push out
+        for event in &wave.events {
+            if let WaveEvent::Obstacle { obstacle } = event {
+                let obstacle = obstacle.scaled(self.table.dimensions);
+                if self.table.holds(&obstacle) && self.is_clear(&obstacle) {
+                    self.obstacles.push(obstacle);
+                }
+            }
+        }
+        self.spawn_items(&wave);
+        self.magnets.clear();
+        let frozen = std::mem::take(&mut self.frozen);
+
+        let mut bombs = Vec::new();
+        for (index, ball) in self.balls.iter().enumerate() {
+            let mut ball = ball.borrow_mut();
+            let (id, position) = (ball.id, ball.position);
+            if let BallType::Enemy(enemy_data) = &mut ball.letypedelaboule
+                && !wave.is_calm()
+                && !frozen
+            {
+                enemy_data.timer -= 1;
+                if enemy_data.timer < 1 {
+                    events.push(Event::TimerExpired {
+                        ball: id,
+                        archetype: enemy_data.archetype,
+                        position,
+                    });
+                    if enemy_data.archetype == Archetype::Bomb {
+                        bombs.push(index);
+                    } else {
+                        self.game_over = true
+                    }
+                }
+            }
+            ball.speed = Vec2::ZERO;
+        }
+        for index in bombs.into_iter().rev() {
+            self.explode(index);
+        }
+        self.spawn_enemies(&wave);
+        if !self.game_over {
+            self.round += 1;
+        }
+        events
+    }
+
+    /// Radius, mass and friction of an enemy
+    fn enemy_body(archetype: Archetype, friction: f32) -> (f32, f32, f32) {
+        match archetype {
+            Archetype::Anchor => (ANCHOR_BALL_SIZE, ANCHOR_MASS, friction.powi(ANCHOR_DRAG)),
+            _ => (ENEMY_BALL_SIZE, ENEMY_MASS, friction),
+        }
+    }
+
+    /// Remove a bomb and push the balls around it away, lighter balls flying further
+    fn explode(&mut self, index: usize) {
+        let bomb = self.balls.remove(index).into_inner();
+
+        for ball in &self.balls {
+            let mut ball = ball.borrow_mut();
+            let offset = ball.position - bomb.position;
+            let distance = offset.length();
+
+            if distance < BOMB_BLAST_RADIUS {
+                let push = BOMB_PUSH * (1. - distance / BOMB_BLAST_RADIUS) * ENEMY_MASS / ball.mass;
+                ball.speed += offset.normalize_or_zero() * push;
+            }
+        }
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.game_over
+    }
+
+    /// Combos of the launched round so far
+    pub fn round_score(&self) -> RoundScore {
+        self.combo.score()
+    }
+
+    pub fn ball(&self, id: BallId) -> Option<&RefCell<entity::Ball>> {
+        self.balls.iter().find(|ball| ball.borrow().id == id)
+    }
+
+    fn add_ball(
+        &mut self,
+        position: Vec2,
+        radius: f32,
+        mass: f32,
+        friction_coeff: f32,
+        letypedelaboule: entity::BallType,
+    ) {
+        let new_ball = Ball {
+            id: self.ball_ids.allocate(),
+            position,
+            radius,
+            mass,
+            speed: Vec2::ZERO,
+            friction_coeff,
+            letypedelaboule,
+        };
+        self.balls.push(RefCell::new(new_ball));
+    }
+
+    pub fn tick(&mut self) -> (bool, Vec<Event>) {
+        let mut trash = Vec::new();
+        let mut new_balls = Vec::new();
+        let mut item_trash: Vec<usize> = Vec::new();
+        let mut merges = Vec::new();
+        let mut events = Vec::new();
+
+        // Each ball is bucketed by the box covering its whole move, so fast balls still meet
+        let largest_move = self.balls.iter().fold(0f32, |largest, ball| {
+            let ball = ball.borrow();
+            largest.max(ball.radius * 2. + ball.speed.length())
+        });
+        self.ball_grid
+            .reset(self.table.dimensions, Self::grid_cell_size(largest_move));
+        for (index, ball) in self.balls.iter().enumerate() {
+            let ball = ball.borrow();
+            let destination = ball.position + ball.speed;
+            self.ball_grid.insert(
+                index,
+                ball.position.min(destination) - ball.radius,
+                ball.position.max(destination) + ball.radius,
+            );
+        }
+
+        let mut contacts = Vec::new();
+        for (a, b) in self.ball_grid.pairs() {
+            let ball_a = self.balls[a].borrow();
+            let ball_b = self.balls[b].borrow();
+            if Self::passes_through(&ball_a, &ball_b) {
+                continue;
+            }
+            if let Some(toi) = physics::time_of_impact(&ball_a, &ball_b) {
+                contacts.push((a, b, toi));
+            }
+        }
+
+        // Contacts are handled in the order they happen during the tick
+        contacts.sort_by(|a, b| a.2.total_cmp(&b.2));
+
+        // Speeds the balls move at, before friction and the cushions change them
+        let moved: Vec<Vec2> = self.balls.iter().map(|ball| ball.borrow().speed).collect();
+        for ball in &self.balls {
+            let mut ball = ball.borrow_mut();
+            self.move_ball(&mut ball, &mut events);
+            ball.speed = ball.speed * ball.friction_coeff;
+            Self::check_border(&mut ball, self.table.dimensions, &mut events);
+        }
+
+        for (a, b, toi) in contacts {
+            if trash.contains(&a) || trash.contains(&b) {
+                continue;
+            }
+            match self.collide(a, b, toi, [moved[a], moved[b]], &mut events) {
+                Some(Collision::Merge(new_ball)) => {
+                    trash.push(a);
+                    trash.push(b);
+                    merges.push((self.balls[a].borrow().id, new_ball.id));
+                    merges.push((self.balls[b].borrow().id, new_ball.id));
+                    new_balls.push(RefCell::new(new_ball));
+                }
+                Some(Collision::Split(index, halves)) => {
+                    trash.push(index);
+                    new_balls.extend(halves.map(RefCell::new));
+                }
+                None => {}
+            }
+        }
+
+        self.pull_coins(&trash);
+
+        self.item_grid.clear();
+        for (index, item) in self.items.iter().enumerate() {
+            self.item_grid.insert(
+                index,
+                item.position - item.radius(),
+                item.position + item.radius(),
+            );
+        }
+
+        let mut nearby_items = Vec::new();
+        for (index, ball_cell) in self.balls.iter().enumerate() {
+            let ball = ball_cell.borrow();
+            if !trash.contains(&index)
+                && let Some(pocket) = self.pocket_of(&ball)
+            {
+                trash.push(index);
+                events.push(Event::Pocketed {
+                    ball: ball.id,
+                    kind: ball.letypedelaboule,
+                    pocket,
+                });
+            }
+
+            let Some(owner) = ball.letypedelaboule.owner() else {
+                continue;
+            };
+
+            self.item_grid.query(
+                ball.position - ball.radius,
+                ball.position + ball.radius,
+                &mut nearby_items,
+            );
+            for &item_index in &nearby_items {
+                let item = self.items[item_index];
+                if item_trash.contains(&item_index)
+                    || ball.radius + item.radius() - (ball.position - item.position).length() <= 0.
+                {
+                    continue;
+                }
+                item_trash.push(item_index);
+
+                match item.kind {
+                    ItemKind::Coin => {
+                        let mut value = COIN_PRICE * self.stats.coin_multiplier;
+                        let power_ups = &mut self.power_ups[owner.0 as usize];
+                        if power_ups.doubled_coins > 0 {
+                            power_ups.doubled_coins -= 1;
+                            value *= 2;
+                        }
+                        events.push(Event::CoinCollected {
+                            ball: ball.id,
+                            position: item.position,
+                            value,
+                        });
+                        self.money += value;
+                        self.player_money[owner.0 as usize] += value;
+                    }
+                    kind => {
+                        let power_ups = &mut self.power_ups[owner.0 as usize];
+                        match kind {
+                            ItemKind::Magnet => self.magnets.push(ball.id),
+                            ItemKind::Freeze => self.frozen = true,
+                            ItemKind::DoubleCoin => power_ups.doubled_coins += 1,
+                            ItemKind::TripleSplit => power_ups.triple_splits.push(ball.id),
+                            ItemKind::Coin => unreachable!(),
+                        }
+                        events.push(Event::PowerUpCollected {
+                            ball: ball.id,
+                            kind,
+                            position: item.position,
+                        });
+                    }
+                }
+            }
+        }
+
+        let combos = self.combo.record(&events, &self.table);
+        events.extend(combos);
+
+        trash.sort();
+        item_trash.sort();
+
+        for bye_bye in item_trash.into_iter().rev() {
+            self.items.remove(bye_bye);
+        }
+        for bye_bye in trash.into_iter().rev() {
+            self.balls.remove(bye_bye);
+        }
+
+        self.balls.extend(new_balls);
+        self.keep_triple_splits(&merges);
+
+        for ball in &self.balls {
+            let ball = ball.borrow();
+            if ball.speed.length() > 0.00003 {
+                return (true, events);
+            }
+        }
+
+        let mut player_ball = false;
+
+        for ball in &self.balls {
+            if ball.borrow().letypedelaboule.is_player() {
+                player_ball = true;
+            }
+        }
+        self.game_over |= !player_ball;
+
+        if self.combo.is_active() {
+            let (score, bonuses) = self.combo.finish(&self.player_money);
+            for (money, bonus) in self.player_money.iter_mut().zip(bonuses) {
+                *money += bonus;
+            }
+            self.money += score.bonus;
+            events.push(Event::RoundScored { score });
+        }
+
+        (false, events)
+    }
+
+    /// Hand the triple splits of merged slimes to the slime they became, the ones of slimes that
+    /// left the table are lost
+    fn keep_triple_splits(&mut self, merges: &[(BallId, BallId)]) {
+        for power_ups in &mut self.power_ups {
+            for slime in &mut power_ups.triple_splits {
+                if let Some(&(_, merged)) = merges.iter().find(|(id, _)| id == slime) {
+                    *slime = merged;
+                }
+            }
+            power_ups
+                .triple_splits
+                .retain(|&slime| self.balls.iter().any(|ball| ball.borrow().id == slime));
+        }
+    }
+
+    /// Move the coins near magnetic slimes towards them, slimes about to be removed don't pull
+    fn pull_coins(&mut self, trash: &[usize]) {
+        for (index, ball) in self.balls.iter().enumerate() {
+            let ball = ball.borrow();
+            if trash.contains(&index) || !self.magnets.contains(&ball.id) {
+                continue;
+            }
+
+            for item in &mut self.items {
+                let offset = ball.position - item.position;
+                let distance = offset.length();
+                if item.kind == ItemKind::Coin && distance < items::MAGNET_RADIUS {
+                    item.position += offset.normalize_or_zero() * distance.min(items::MAGNET_PULL);
+                }
+            }
+        }
+    }
+
+    /// Move a ball by its speed, stopping at the first obstacle on the way to bounce off it. The
+    /// rest of the move after the bounce is dropped.
+    fn move_ball(&self, ball: &mut Ball, events: &mut Vec<Event>) {
+        let first_hit = self
+            .obstacles
+            .iter()
+            .enumerate()
+            .filter_map(|(index, obstacle)| {
+                obstacle
+                    .time_of_impact(ball.position, ball.speed, ball.radius)
+                    .map(|toi| (toi, index))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+
+        match first_hit {
+            Some((toi, index)) => {
+                ball.position += ball.speed * toi;
+                if let Some(contact) = self.obstacles[index].bounce(ball)
+                    && contact.impulse > 0.
+                {
+                    events.push(Event::Bounce {
+                        ball: ball.id,
+                        obstacle: Some(index),
+                        point: contact.point,
+                        impulse: contact.impulse,
+                    });
+                }
+            }
+            None => ball.position += ball.speed,
+        }
+    }
+
+    /// Cells just big enough for the largest move keep few balls per cell, rounded to a power of
+    /// two so the grid isn't rebuilt every tick
+    fn grid_cell_size(largest_move: f32) -> f32 {
+        largest_move.max(MIN_GRID_CELL_SIZE).log2().ceil().exp2()
+    }
+
+    /// Ghosts don't touch other enemies
+    fn passes_through(a: &entity::Ball, b: &entity::Ball) -> bool {
+        match (a.letypedelaboule, b.letypedelaboule) {
+            (BallType::Enemy(a), BallType::Enemy(b)) => {
+                a.archetype == Archetype::Ghost || b.archetype == Archetype::Ghost
+            }
+            _ => false,
+        }
+    }
+
+    /// Index of the pocket the ball fell in
+    fn pocket_of(&self, ball: &entity::Ball) -> Option<usize> {
+        if let BallType::Enemy(enemy) = ball.letypedelaboule
+            && enemy.shield
+        {
+            return None;
+        }
+        self.hole_grid
+            .at(ball.position)
+            .iter()
+            .copied()
+            .find(|&hole| {
+                let pocket = self.table.pockets[hole];
+                pocket.radius > ball.position.distance(pocket.position)
+            })
+    }
+
+    /// Split each ball launched by `player` in two halves going opposite ways, returns the ids of
+    /// the pieces of each ball, the first one going the way of the velocity. A slime holding a triple
+    /// split breaks in three pieces spread evenly around the velocity instead.
+    /// Players shooting during the same round are each launched before the first tick.
+    pub fn launch_round(
+        &mut self,
+        player: PlayerId,
+        velocities: &BTreeMap<BallId, Vec2>,
+    ) -> Result<BTreeMap<BallId, Vec<BallId>>, LaunchError> {
+        for (&id, velocity) in velocities {
+            let Some(ball) = self.ball(id) else {
+                return Err(LaunchError::UnknownBall(id));
+            };
+            match ball.borrow().letypedelaboule.owner() {
+                None => return Err(LaunchError::NotASlime(id)),
+                Some(owner) if owner != player => return Err(LaunchError::NotYours(id)),
+                Some(_) => {}
+            }
+            if *velocity == Vec2::ZERO {
+                return Err(LaunchError::ZeroAim(id));
+            }
+            if !velocity.is_finite() || velocity.length() > MAX_AIM + AIM_TOLERANCE {
+                return Err(LaunchError::AimTooLong(id));
+            }
+        }
+
+        let mut new_balls = vec![];
+        let mut pieces = BTreeMap::new();
+        for (id, velocity) in velocities {
+            let ball = *self.ball(*id).unwrap().borrow();
+            let velocity = *velocity * self.stats.max_speed;
+
+            let triple_splits = &mut self.power_ups[player.0 as usize].triple_splits;
+            let speeds = if let Some(index) = triple_splits.iter().position(|slime| slime == id) {
+                triple_splits.remove(index);
+                vec![
+                    velocity,
+                    Vec2::from_angle(TRIPLE_SPLIT_ANGLE).rotate(velocity),
+                    Vec2::from_angle(-TRIPLE_SPLIT_ANGLE).rotate(velocity),
+                ]
+            } else {
+                vec![velocity, velocity * -1.]
+            };
+            let count = speeds.len() as f32;
+
+            let mut ids = Vec::new();
+            for speed in speeds {
+                let piece = Ball::new(
+                    self.ball_ids.allocate(),
+                    ball.mass / count,
+                    ball.position + speed.normalize_or_zero() * ball.radius,
+                    speed,
+                    ball.friction_coeff,
+                    ball.radius / count.sqrt(),
+                    ball.letypedelaboule,
+                );
+                ids.push(piece.id);
+                new_balls.push(RefCell::new(piece));
+            }
+            pieces.insert(*id, ids);
+        }
+
+        self.balls
+            .retain(|ball| !velocities.contains_key(&ball.borrow().id));
+
+        self.balls.extend(new_balls);
+        self.combo.start(&self.player_money);
+
+        Ok(pieces)
+    }
+
+    fn check_border(ball: &mut entity::Ball, dimensions: Vec2, events: &mut Vec<Event>) {
+        let mut bounce = |ball: &mut entity::Ball, point: Vec2, speed: f32| {
+            events.push(Event::Bounce {
+                ball: ball.id,
+                obstacle: None,
+                point,
+                impulse: 2. * ball.mass * speed.abs(),
+            });
+        };
+
+        if ball.position.x - ball.radius < 0. {
+            ball.position.x = ball.radius;
+            bounce(ball, Vec2::new(0., ball.position.y), ball.speed.x);
+            ball.speed.x = -ball.speed.x;
+        } else if ball.position.x + ball.radius > dimensions.x {
+            ball.position.x = dimensions.x - ball.radius;
+            bounce(ball, Vec2::new(dimensions.x, ball.position.y), ball.speed.x);
+            ball.speed.x = -ball.speed.x;
+        }
+
+        if ball.position.y - ball.radius < 0. {
+            ball.position.y = ball.radius;
+            bounce(ball, Vec2::new(ball.position.x, 0.), ball.speed.y);
+            ball.speed.y = -ball.speed.y;
+        } else if ball.position.y + ball.radius > dimensions.y {
+            ball.position.y = dimensions.y - ball.radius;
+            bounce(ball, Vec2::new(ball.position.x, dimensions.y), ball.speed.y);
+            ball.speed.y = -ball.speed.y;
+        }
+    }
+
+    /// Handle a contact found by the sweep, `moved` being the speeds the balls moved at during the
+    /// tick. Returns what changed when two slimes merge or a slime breaks an enemy.
+    pub fn collide(
+        &self,
+        a: usize,
+        b: usize,
+        toi: f32,
+        moved: [Vec2; 2],
+        events: &mut Vec<Event>,
+    ) -> Option<Collision> {
+        let mut ball_a = self.balls[a].borrow_mut();
+        let mut ball_b = self.balls[b].borrow_mut();
+
+        // Slimes of different players bounce off each other like any other ball
+        if let (BallType::Player(owner_a), BallType::Player(owner_b)) =
+            (ball_a.letypedelaboule, ball_b.letypedelaboule)
+            && owner_a == owner_b
+        {
+            let merged = physics::merge(&ball_a, &ball_b, self.ball_ids.allocate());
+            events.push(Event::Merged {
+                balls: [ball_a.id, ball_b.id],
+                into: merged.id,
+                position: merged.position,
+            });
+            return Some(Collision::Merge(merged));
+        }
+
+        // The balls went through each other during the tick, bounce them where they touched
+        let tunnelled = ball_a.position.distance(ball_b.position) > ball_a.radius + ball_b.radius;
+        let rewind = if tunnelled { 1. - toi } else { 0. };
+
+        ball_a.position -= moved[0] * rewind;
+        ball_b.position -= moved[1] * rewind;
+
+        let contact = physics::resolve(&mut ball_a, &mut ball_b, physics::BALL_RESTITUTION);
+
+        let speed_a = ball_a.speed;
+        let speed_b = ball_b.speed;
+        ball_a.position += speed_a * rewind;
+        ball_b.position += speed_b * rewind;
+
+        let contact = contact.filter(|contact| contact.impulse > 0.)?;
+        events.push(Event::Collision {
+            balls: [ball_a.id, ball_b.id],
+            kinds: [ball_a.letypedelaboule, ball_b.letypedelaboule],
+            point: contact.point,
+            impulse: contact.impulse,
+        });
+
+        if ball_b.letypedelaboule.is_player() {
+            self.hit_enemy(&mut ball_a, a, contact.normal, events)
+        } else if ball_a.letypedelaboule.is_player() {
+            self.hit_enemy(&mut ball_b, b, contact.normal, events)
+        } else {
+            None
+        }
+    }
+
+    /// A slime hit this enemy
+    fn hit_enemy(
+        &self,
+        enemy: &mut Ball,
+        index: usize,
+        normal: Vec2,
+        events: &mut Vec<Event>,
+    ) -> Option<Collision> {
+        let (id, position) = (enemy.id, enemy.position);
+        let BallType::Enemy(enemy_data) = &mut enemy.letypedelaboule else {
+            return None;
+        };
+
+        match enemy_data.archetype {
+            Archetype::Shielded => {
+                if enemy_data.shield {
+                    events.push(Event::ShieldBroken { ball: id, position });
+                }
+                enemy_data.shield = false;
+                None
+            }
+            Archetype::Splitter => {
+                let data = EnemyData::new(Archetype::Basic, enemy_data.timer);
+                let side = normal.perp();
+                let radius = enemy.radius / 2f32.sqrt();
+                let push = side * enemy.speed.length() * SPLIT_SPEED;
+
+                let half = |sign: f32| {
+                    Ball::new(
+                        self.ball_ids.allocate(),
+                        enemy.mass / 2.,
+                        enemy.position + side * radius * sign,
+                        enemy.speed + push * sign,
+                        enemy.friction_coeff,
+                        radius,
+                        BallType::Enemy(data),
+                    )
+                };
+
+                let halves = [half(1.), half(-1.)];
+                events.push(Event::Split {
+                    ball: id,
+                    into: halves.map(|half| half.id),
+                    position,
+                });
+                Some(Collision::Split(index, halves))
+            }
+            _ => None,
+        }
+    }
+}
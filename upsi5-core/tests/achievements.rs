@@ -0,0 +1,171 @@
+use glam::Vec2;
+use upsi5_core::{
+    achievements::{
+        Achievement, AchievementProgress, AchievementRegistry, AchievementTracker, RegistryError,
+    },
+    entity::{Archetype, BallId, BallType, EnemyData, PlayerId},
+    events::Event,
+    upgrades::UpgradeLevels,
+};
+
+fn pocketed(kind: BallType) -> Event {
+    Event::Pocketed {
+        ball: BallId(1),
+        kind,
+        pocket: 0,
+    }
+}
+
+fn enemy_pocketed() -> Event {
+    pocketed(BallType::Enemy(EnemyData::new(Archetype::Basic, 5)))
+}
+
+fn merged() -> Event {
+    Event::Merged {
+        balls: [BallId(1), BallId(2)],
+        into: BallId(3),
+        position: Vec2::ZERO,
+    }
+}
+
+fn ids(unlocked: Vec<&Achievement>) -> Vec<&str> {
+    unlocked.iter().map(|a| a.id.as_str()).collect()
+}
+
+#[test]
+fn builtin_registry_is_valid() {
+    let registry = AchievementRegistry::builtin();
+
+    assert!(registry.get("hat_trick").is_some());
+    assert!(registry.get("blindfolded").is_some());
+    assert!(registry.get("slime_party").is_some());
+}
+
+#[test]
+fn invalid_registries_are_rejected() {
+    let duplicate = r#"[
+        {"id": "a", "name": "A", "description": "", "goal": {"kind": "round", "round": 2}},
+        {"id": "a", "name": "B", "description": "", "goal": {"kind": "round", "round": 3}}
+    ]"#;
+    assert!(AchievementRegistry::from_json(duplicate).is_err());
+
+    let free =
+        r#"[{"id": "a", "name": "A", "description": "", "goal": {"kind": "round", "round": 0}}]"#;
+    assert_eq!(
+        AchievementRegistry::from_json(free).err(),
+        Some(RegistryError("a is unlocked without playing".to_owned()))
+    );
+}
+
+#[test]
+fn pockets_of_one_shot_add_up_over_its_ticks() {
+    let registry = AchievementRegistry::builtin();
+    let mut tracker = AchievementTracker::new(registry, AchievementProgress::default());
+    tracker.start_run(&UpgradeLevels::default());
+
+    tracker.round_launched();
+    assert!(tracker.on_tick(&[enemy_pocketed()]).is_empty());
+    // Slimes falling in don't count
+    assert!(
+        tracker
+            .on_tick(&[pocketed(BallType::Player(PlayerId(0)))])
+            .is_empty()
+    );
+    assert!(tracker.on_tick(&[enemy_pocketed()]).is_empty());
+
+    // A new shot starts from zero
+    tracker.round_launched();
+    assert!(
+        tracker
+            .on_tick(&[enemy_pocketed(), enemy_pocketed()])
+            .is_empty()
+    );
+    assert_eq!(ids(tracker.on_tick(&[enemy_pocketed()])), ["hat_trick"]);
+    // Unlocked once only
+    assert!(tracker.on_tick(&[enemy_pocketed(); 3]).is_empty());
+    assert!(tracker.progress.is_unlocked("hat_trick"));
+}
+
+#[test]
+fn merges_are_counted_per_round() {
+    let registry = AchievementRegistry::builtin();
+    let mut tracker = AchievementTracker::new(registry, AchievementProgress::default());
+
+    tracker.round_launched();
+    assert!(tracker.on_tick(&[merged(), merged(), merged()]).is_empty());
+    tracker.round_launched();
+    assert!(tracker.on_tick(&[merged(), merged()]).is_empty());
+    assert_eq!(ids(tracker.on_tick(&[merged(), merged()])), ["slime_party"]);
+}
+
+#[test]
+fn rounds_without_an_upgrade_need_a_run_without_it() {
+    let registry = AchievementRegistry::builtin();
+    let blindfolded = registry.get("blindfolded").unwrap();
+    let mut tracker = AchievementTracker::new(registry, AchievementProgress::default());
+
+    let mut aimed = UpgradeLevels::default();
+    aimed.set("aim_assist", 1);
+    tracker.start_run(&aimed);
+    for round in 1..=25 {
+        assert!(!ids(tracker.on_spawn_round(round)).contains(&"blindfolded"));
+    }
+    assert_eq!(tracker.progress.progress(blindfolded), 0);
+
+    tracker.start_run(&UpgradeLevels::default());
+    for round in 1..20 {
+        tracker.on_spawn_round(round);
+    }
+    assert_eq!(tracker.progress.progress(blindfolded), 19);
+    assert_eq!(ids(tracker.on_spawn_round(20)), ["blindfolded"]);
+}
+
+#[test]
+fn totals_carry_over_between_sessions() {
+    let registry = AchievementRegistry::builtin();
+    let exterminator = registry.get("exterminator").unwrap();
+
+    let mut tracker = AchievementTracker::new(registry, AchievementProgress::default());
+    for _ in 0..200 {
+        tracker.round_launched();
+        tracker.on_tick(&[enemy_pocketed()]);
+    }
+    let saved = tracker.progress.clone();
+    assert_eq!(saved.progress(exterminator), 200);
+
+    let mut tracker = AchievementTracker::new(registry, saved);
+    for _ in 0..49 {
+        tracker.round_launched();
+        assert!(tracker.on_tick(&[enemy_pocketed()]).is_empty());
+    }
+    tracker.round_launched();
+    assert_eq!(ids(tracker.on_tick(&[enemy_pocketed()])), ["exterminator"]);
+    assert_eq!(tracker.progress.progress(exterminator), 250);
+}
+
+#[test]
+fn coins_count_once_whatever_their_value() {
+    let registry = AchievementRegistry::builtin();
+    let hoarder = registry.get("hoarder").unwrap();
+    let mut tracker = AchievementTracker::new(registry, AchievementProgress::default());
+
+    tracker.on_tick(&[Event::CoinCollected {
+        ball: BallId(1),
+        position: Vec2::ZERO,
+        value: 400,
+    }]);
+
+    assert_eq!(tracker.progress.progress(hoarder), 1);
+}
+
+#[test]
+fn shop_unlocks_maxed_upgrades() {
+    let registry = AchievementRegistry::builtin();
+    let mut tracker = AchievementTracker::new(registry, AchievementProgress::default());
+
+    let mut levels = UpgradeLevels::default();
+    levels.set("aim_assist", 2);
+    assert!(tracker.on_purchase(&levels).is_empty());
+    levels.set("aim_assist", 3);
+    assert_eq!(ids(tracker.on_purchase(&levels)), ["maxed_out"]);
+}
@@ -0,0 +1,161 @@
+mod common;
+
+use common::Scene;
+use glam::Vec2;
+use upsi5_core::{
+    entity::{Archetype, Ball, BallId, BallType, EnemyData, PlayerId},
+    world::World,
+};
+
+fn enemy(archetype: Archetype, timer: usize) -> BallType {
+    BallType::Enemy(EnemyData::new(archetype, timer))
+}
+
+fn run(world: &mut World, ticks: usize) {
+    for _ in 0..ticks {
+        world.tick();
+    }
+}
+
+#[test]
+fn ghosts_go_through_enemies() {
+    let mut world = Scene::new()
+        .moving(&[
+            (
+                Vec2::new(0.5, 0.5),
+                Vec2::new(0.01, 0.),
+                enemy(Archetype::Ghost, 5),
+            ),
+            (Vec2::new(0.6, 0.5), Vec2::ZERO, enemy(Archetype::Basic, 5)),
+        ])
+        .build();
+
+    run(&mut world, 30);
+
+    let basic = world.ball(BallId(101)).unwrap().borrow();
+    assert_eq!(basic.speed, Vec2::ZERO);
+    assert_eq!(basic.position, Vec2::new(0.6, 0.5));
+}
+
+#[test]
+fn ghosts_still_hit_slimes() {
+    let mut world = Scene::new()
+        .moving(&[
+            (
+                Vec2::new(0.5, 0.5),
+                Vec2::new(0.01, 0.),
+                BallType::Player(PlayerId(0)),
+            ),
+            (Vec2::new(0.6, 0.5), Vec2::ZERO, enemy(Archetype::Ghost, 5)),
+        ])
+        .build();
+
+    run(&mut world, 30);
+
+    assert!(world.ball(BallId(101)).unwrap().borrow().speed.x > 0.);
+}
+
+#[test]
+fn splitters_break_when_a_slime_hits_them() {
+    let mut world = Scene::new()
+        .moving(&[
+            (
+                Vec2::new(0.5, 0.5),
+                Vec2::new(0.01, 0.),
+                BallType::Player(PlayerId(0)),
+            ),
+            (
+                Vec2::new(0.6, 0.5),
+                Vec2::ZERO,
+                enemy(Archetype::Splitter, 4),
+            ),
+        ])
+        .build();
+
+    run(&mut world, 30);
+
+    let halves: Vec<Ball> = world
+        .balls
+        .iter()
+        .map(|b| *b.borrow())
+        .filter(|b| b.letypedelaboule != BallType::Player(PlayerId(0)))
+        .collect();
+    assert_eq!(halves.len(), 2);
+    for half in halves {
+        assert!(half.letypedelaboule == enemy(Archetype::Basic, 4));
+        assert!((half.mass - 0.075).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn shields_keep_enemies_out_of_holes_until_hit() {
+    let mut world = Scene::new()
+        .balls(&[(Vec2::new(0.038, 0.038), enemy(Archetype::Shielded, 5))])
+        .build();
+
+    world.tick();
+    assert_eq!(world.balls.len(), 1);
+
+    if let BallType::Enemy(data) = &mut world.balls[0].borrow_mut().letypedelaboule {
+        data.shield = false;
+    }
+    world.tick();
+    assert!(world.balls.is_empty());
+}
+
+#[test]
+fn slimes_break_shields() {
+    let mut world = Scene::new()
+        .moving(&[
+            (
+                Vec2::new(0.5, 0.5),
+                Vec2::new(0.01, 0.),
+                BallType::Player(PlayerId(0)),
+            ),
+            (
+                Vec2::new(0.6, 0.5),
+                Vec2::ZERO,
+                enemy(Archetype::Shielded, 5),
+            ),
+        ])
+        .build();
+
+    run(&mut world, 30);
+
+    let BallType::Enemy(data) = world.ball(BallId(101)).unwrap().borrow().letypedelaboule else {
+        panic!("the shielded enemy turned into a slime");
+    };
+    assert!(!data.shield);
+}
+
+#[test]
+fn bombs_explode_instead_of_ending_the_run() {
+    let mut world = Scene::new()
+        .balls(&[
+            (Vec2::new(0.3, 0.3), BallType::Player(PlayerId(0))),
+            (Vec2::new(1., 0.5), enemy(Archetype::Bomb, 1)),
+            (Vec2::new(1.1, 0.5), enemy(Archetype::Basic, 5)),
+        ])
+        .build();
+
+    world.spawn_round();
+
+    assert!(!world.is_game_over());
+    assert!(world.ball(BallId(101)).is_none());
+    assert!(world.ball(BallId(102)).unwrap().borrow().speed.x > 0.);
+    assert_eq!(world.ball(BallId(100)).unwrap().borrow().speed, Vec2::ZERO);
+}
+
+#[test]
+fn other_enemies_end_the_run() {
+    let mut world = Scene::new()
+        .balls(&[
+            (Vec2::new(0.3, 0.3), BallType::Player(PlayerId(0))),
+            (Vec2::new(1., 0.5), enemy(Archetype::Anchor, 1)),
+        ])
+        .build();
+
+    world.spawn_round();
+
+    assert!(world.is_game_over());
+}
@@ -0,0 +1,72 @@
+use glam::Vec2;
+use std::collections::BTreeMap;
+use upsi5_core::{
+    balance::{self, Death, Policy, RandomPolicy, RunStats},
+    entity::{Archetype, BallId},
+    table::TableRegistry,
+    upgrades::UpgradeLevels,
+    world::World,
+};
+
+/// Never shoots, the enemies are left to run out their timers
+struct Idle;
+
+impl Policy for Idle {
+    fn moves(&mut self, _world: &World) -> BTreeMap<BallId, Vec2> {
+        BTreeMap::new()
+    }
+}
+
+fn run(policy: &mut dyn Policy, seed: u64, max_rounds: usize) -> RunStats {
+    balance::simulate_run(
+        seed,
+        &UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+        policy,
+        max_rounds,
+    )
+}
+
+#[test]
+fn runs_are_reproducible() {
+    assert_eq!(
+        run(&mut RandomPolicy::new(4), 4, 50),
+        run(&mut RandomPolicy::new(4), 4, 50)
+    );
+}
+
+#[test]
+fn idle_runs_die_of_their_timers() {
+    let stats = run(&mut Idle, 0, 100);
+
+    assert_eq!(
+        stats.death,
+        Some(Death::Timer {
+            archetype: Archetype::Basic
+        })
+    );
+    assert_eq!(stats.per_round.len(), stats.rounds + 1);
+    assert!(stats.per_round.iter().all(|round| round.slimes == 1));
+    assert_eq!(stats.money, 0);
+}
+
+#[test]
+fn round_limit_stops_surviving_runs() {
+    let stats = run(&mut Idle, 0, 2);
+
+    assert_eq!(stats.death, None);
+    assert_eq!(stats.rounds, 2);
+    assert_eq!(stats.per_round.len(), 2);
+}
+
+#[test]
+fn csv_rows_match_the_header() {
+    let stats = run(&mut RandomPolicy::new(1), 1, 10);
+    let row = stats.csv_row();
+
+    assert_eq!(
+        row.split(',').count(),
+        RunStats::CSV_HEADER.split(',').count()
+    );
+    assert!(row.starts_with(&format!("1,{},{},", stats.rounds, stats.money)));
+}
@@ -0,0 +1,29 @@
+use upsi5_core::base64::{decode, encode};
+
+const VECTORS: [(&str, &str); 7] = [
+    ("", ""),
+    ("f", "Zg=="),
+    ("fo", "Zm8="),
+    ("foo", "Zm9v"),
+    ("foob", "Zm9vYg=="),
+    ("fooba", "Zm9vYmE="),
+    ("foobar", "Zm9vYmFy"),
+];
+
+#[test]
+fn rfc_vectors_round_trip() {
+    for (bytes, text) in VECTORS {
+        assert_eq!(encode(bytes.as_bytes()), text);
+        assert_eq!(decode(text).unwrap(), bytes.as_bytes());
+    }
+
+    let every_byte: Vec<u8> = (0..=255).collect();
+    assert_eq!(decode(&encode(&every_byte)).unwrap(), every_byte);
+}
+
+#[test]
+fn invalid_text_is_rejected() {
+    for text in ["Zg=", "Zg", "Z===", "Zg==Zg==", "Zm9*", "[1,2]"] {
+        assert_eq!(decode(text), None, "{text}");
+    }
+}
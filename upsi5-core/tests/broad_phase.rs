@@ -0,0 +1,46 @@
+use glam::Vec2;
+use upsi5_core::{broad_phase::Grid, rng::Rng};
+
+#[test]
+fn grid_finds_every_overlapping_pair() {
+    let mut rng = Rng::new(3);
+    let mut grid = Grid::new(Vec2::new(2., 1.), 0.08);
+
+    let circles: Vec<(Vec2, f32)> = (0..300)
+        .map(|_| {
+            (
+                Vec2::new(rng.rand_range(-0.1, 2.1) as f32, rng.rand() as f32),
+                rng.rand_range(0.005, 0.12) as f32,
+            )
+        })
+        .collect();
+
+    for (index, &(center, radius)) in circles.iter().enumerate() {
+        grid.insert(index, center - radius, center + radius);
+    }
+
+    let pairs = grid.pairs();
+
+    for a in 0..circles.len() {
+        for b in a + 1..circles.len() {
+            let (center_a, radius_a) = circles[a];
+            let (center_b, radius_b) = circles[b];
+
+            if center_a.distance(center_b) < radius_a + radius_b {
+                assert!(pairs.binary_search(&(a, b)).is_ok(), "missed {a} {b}");
+            }
+        }
+    }
+}
+
+#[test]
+fn query_returns_each_item_once() {
+    let mut grid = Grid::new(Vec2::new(1., 1.), 0.1);
+    grid.insert(4, Vec2::splat(0.05), Vec2::splat(0.55));
+    grid.insert(2, Vec2::splat(0.5), Vec2::splat(0.52));
+
+    let mut result = Vec::new();
+    grid.query(Vec2::ZERO, Vec2::ONE, &mut result);
+
+    assert_eq!(result, [2, 4]);
+}
@@ -0,0 +1,106 @@
+// Each test crate only uses part of the builder
+#![allow(dead_code)]
+
+use glam::Vec2;
+use std::cell::RefCell;
+use upsi5_core::{
+    entity::{Ball, BallId, BallType},
+    items::Item,
+    obstacles::Obstacle,
+    table::TableRegistry,
+    upgrades::UpgradeLevels,
+    world::{ENEMY_BALL_SIZE, World},
+};
+
+/// A world on the default table holding only the given balls, items and obstacles. The balls
+/// get ids from 100 in the order they're added.
+pub struct Scene {
+    players: u8,
+    friction: f32,
+    balls: Vec<(Vec2, Vec2, BallType)>,
+    items: Vec<Item>,
+    obstacles: Vec<Obstacle>,
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scene {
+    pub fn new() -> Self {
+        Self {
+            players: 1,
+            friction: 1.,
+            balls: Vec::new(),
+            items: Vec::new(),
+            obstacles: Vec::new(),
+        }
+    }
+
+    pub fn players(mut self, players: u8) -> Self {
+        self.players = players;
+        self
+    }
+
+    /// Friction coefficient of every ball, they never slow down by default
+    pub fn friction(mut self, friction: f32) -> Self {
+        self.friction = friction;
+        self
+    }
+
+    /// Balls lying still
+    pub fn balls(mut self, balls: &[(Vec2, BallType)]) -> Self {
+        self.balls.extend(
+            balls
+                .iter()
+                .map(|&(position, letypedelaboule)| (position, Vec2::ZERO, letypedelaboule)),
+        );
+        self
+    }
+
+    /// Balls already rolling at the given speed
+    pub fn moving(mut self, balls: &[(Vec2, Vec2, BallType)]) -> Self {
+        self.balls.extend_from_slice(balls);
+        self
+    }
+
+    pub fn items(mut self, items: &[Item]) -> Self {
+        self.items.extend_from_slice(items);
+        self
+    }
+
+    pub fn obstacles(mut self, obstacles: &[Obstacle]) -> Self {
+        self.obstacles.extend_from_slice(obstacles);
+        self
+    }
+
+    pub fn build(self) -> World {
+        let mut world = World::with_players(
+            0,
+            &UpgradeLevels::default(),
+            TableRegistry::builtin().default_table(),
+            self.players,
+        );
+        world.items = self.items;
+        world.obstacles = self.obstacles;
+        world.balls = self
+            .balls
+            .into_iter()
+            .enumerate()
+            .map(|(index, (position, speed, letypedelaboule))| {
+                RefCell::new(Ball::new(
+                    BallId(100 + index as u64),
+                    0.15,
+                    position,
+                    speed,
+                    self.friction,
+                    ENEMY_BALL_SIZE,
+                    letypedelaboule,
+                ))
+            })
+            .collect();
+        world
+    }
+}
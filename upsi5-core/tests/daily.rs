@@ -0,0 +1,55 @@
+use upsi5_core::{
+    daily::{DailyChallenge, Date},
+    upgrades::UpgradeRegistry,
+};
+
+fn date(year: i32, month: u8, day: u8) -> Date {
+    Date { year, month, day }
+}
+
+#[test]
+fn days_map_to_calendar_dates() {
+    assert_eq!(Date::from_days_since_epoch(0), date(1970, 1, 1));
+    assert_eq!(Date::from_days_since_epoch(19_723), date(2024, 1, 1));
+    assert_eq!(Date::from_days_since_epoch(19_782), date(2024, 2, 29));
+    assert_eq!(Date::from_days_since_epoch(-1), date(1969, 12, 31));
+    assert_eq!(date(2024, 2, 29).to_string(), "2024-02-29");
+}
+
+#[test]
+fn the_whole_day_shares_a_date() {
+    let midnight = 19_723. * 86_400_000.;
+
+    assert_eq!(Date::from_unix_millis(midnight), date(2024, 1, 1));
+    assert_eq!(
+        Date::from_unix_millis(midnight + 86_399_999.),
+        date(2024, 1, 1)
+    );
+    assert_eq!(
+        Date::from_unix_millis(midnight + 86_400_000.),
+        date(2024, 1, 2)
+    );
+}
+
+#[test]
+fn everyone_gets_the_same_challenge() {
+    assert_eq!(
+        DailyChallenge::new(date(2026, 10, 18)),
+        DailyChallenge::new(date(2026, 10, 18))
+    );
+    assert_ne!(
+        DailyChallenge::new(date(2026, 10, 18)).seed,
+        DailyChallenge::new(date(2026, 10, 19)).seed
+    );
+}
+
+#[test]
+fn challenge_levels_are_valid() {
+    for day in 1..=28 {
+        let challenge = DailyChallenge::new(date(2026, 2, day));
+
+        for upgrade in UpgradeRegistry::builtin().iter() {
+            assert!(challenge.levels.get(&upgrade.id) <= upgrade.max_level);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+mod common;
+
+use common::Scene;
+use glam::Vec2;
+use upsi5_core::{
+    entity::{Archetype, BallId, BallType, EnemyData, PlayerId},
+    events::Event,
+    items::Item,
+    world::{Sounds, World},
+};
+
+fn run(world: &mut World, ticks: usize) -> Vec<Event> {
+    (0..ticks).flat_map(|_| world.tick().1).collect()
+}
+
+#[test]
+fn collisions_carry_impulse_and_point() {
+    let mut world = Scene::new()
+        .moving(&[
+            (
+                Vec2::new(0.5, 0.5),
+                Vec2::new(0.01, 0.),
+                BallType::Player(PlayerId(0)),
+            ),
+            (
+                Vec2::new(0.6, 0.5),
+                Vec2::ZERO,
+                BallType::Enemy(EnemyData::new(Archetype::Basic, 5)),
+            ),
+        ])
+        .build();
+
+    let events = run(&mut world, 30);
+
+    let [event] = events[..] else {
+        panic!("expected a single collision, got {events:?}");
+    };
+    let Event::Collision {
+        balls,
+        point,
+        impulse,
+        ..
+    } = event
+    else {
+        panic!("expected a collision, got {event:?}");
+    };
+    assert_eq!(balls, [BallId(100), BallId(101)]);
+    assert!((point.y - 0.5).abs() < 1e-4);
+    assert!(point.x > 0.5 && point.x < 0.6);
+    assert!(impulse > 0.);
+    assert_eq!(event.sound(), Some(Sounds::SlimeEnemy));
+}
+
+#[test]
+fn border_bounces_are_reported() {
+    let mut world = Scene::new()
+        .moving(&[(
+            Vec2::new(0.04, 0.5),
+            Vec2::new(-0.01, 0.),
+            BallType::Player(PlayerId(0)),
+        )])
+        .build();
+
+    let events = run(&mut world, 2);
+
+    assert_eq!(
+        events,
+        [Event::Bounce {
+            ball: BallId(100),
+            obstacle: None,
+            point: Vec2::new(0., 0.5),
+            impulse: 2. * 0.15 * 0.01,
+        }]
+    );
+}
+
+#[test]
+fn pockets_are_identified() {
+    let world = Scene::new().build();
+    let pocket = world.table().pockets.len() - 1;
+    let position = world.table().pockets[pocket].position;
+
+    let mut world = Scene::new()
+        .balls(&[(position, BallType::Player(PlayerId(0)))])
+        .build();
+    let events = run(&mut world, 1);
+
+    assert_eq!(
+        events,
+        [Event::Pocketed {
+            ball: BallId(100),
+            kind: BallType::Player(PlayerId(0)),
+            pocket,
+        }]
+    );
+}
+
+#[test]
+fn coins_report_their_value() {
+    let mut world = Scene::new()
+        .balls(&[(Vec2::new(0.5, 0.5), BallType::Player(PlayerId(0)))])
+        .items(&[
+            Item::coin(Vec2::new(0.51, 0.5)),
+            Item::coin(Vec2::new(1.5, 0.5)),
+        ])
+        .build();
+
+    let events = run(&mut world, 1);
+
+    assert_eq!(
+        events,
+        [Event::CoinCollected {
+            ball: BallId(100),
+            position: Vec2::new(0.51, 0.5),
+            value: world.money,
+        }]
+    );
+    assert_eq!(world.money, 100);
+}
+
+#[test]
+fn merges_name_both_slimes() {
+    let mut world = Scene::new()
+        .moving(&[
+            (
+                Vec2::new(0.5, 0.5),
+                Vec2::new(0.01, 0.),
+                BallType::Player(PlayerId(0)),
+            ),
+            (
+                Vec2::new(0.6, 0.5),
+                Vec2::ZERO,
+                BallType::Player(PlayerId(0)),
+            ),
+        ])
+        .build();
+
+    let events = run(&mut world, 30);
+
+    let merged = world.balls[0].borrow().id;
+    assert!(matches!(
+        events[..],
+        [Event::Merged { balls: [BallId(100), BallId(101)], into, .. }] if into == merged
+    ));
+}
+
+#[test]
+fn expired_timers_come_from_spawn_round() {
+    let mut world = Scene::new()
+        .balls(&[
+            (Vec2::new(0.3, 0.3), BallType::Player(PlayerId(0))),
+            (
+                Vec2::new(1., 0.5),
+                BallType::Enemy(EnemyData::new(Archetype::Bomb, 1)),
+            ),
+        ])
+        .build();
+
+    let events = world.spawn_round();
+
+    assert_eq!(
+        events,
+        [Event::TimerExpired {
+            ball: BallId(101),
+            archetype: Archetype::Bomb,
+            position: Vec2::new(1., 0.5),
+        }]
+    );
+}
@@ -0,0 +1,128 @@
+use upsi5_core::{
+    balance::{Policy, RandomPolicy},
+    entity::PlayerId,
+    ghost::Ghost,
+    replay::Replay,
+    table::TableRegistry,
+    upgrades::UpgradeLevels,
+    world::World,
+};
+
+/// Play a few rounds with random shots and record them
+fn recorded_run(seed: u64, rounds: usize) -> Replay {
+    let mut replay = Replay::new(
+        seed,
+        UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+    );
+    let mut world = replay.world();
+    let mut policy = RandomPolicy::new(seed);
+
+    while !world.is_game_over() && replay.rounds.len() < rounds {
+        let moves = policy.moves(&world);
+        world.launch_round(PlayerId(0), &moves).unwrap();
+        replay.record(world.round, PlayerId(0), &moves);
+        while world.tick().0 {}
+        world.spawn_round();
+    }
+
+    replay
+}
+
+/// Play the recorded run again live, launching and ticking the ghost like the game does
+fn race(replay: &Replay, mut check: impl FnMut(&World, &Ghost)) {
+    let mut world = replay.world();
+    let mut ghost = Ghost::new("ada", replay.clone());
+
+    for recorded in &replay.rounds {
+        world
+            .launch_round(recorded.player, &recorded.moves)
+            .unwrap();
+        ghost.launch(world.round).unwrap();
+        loop {
+            let run = world.tick().0;
+            ghost.tick().unwrap();
+            if !run {
+                world.spawn_round();
+            }
+            check(&world, &ghost);
+            if !run {
+                break;
+            }
+        }
+    }
+}
+
+#[test]
+fn same_shots_keep_the_ghost_level() {
+    let replay = recorded_run(3, 6);
+    let mut ticks = 0;
+
+    race(&replay, |world, ghost| {
+        assert_eq!(ghost.world().round, world.round);
+        assert_eq!(ghost.money_ahead(world.money), 0);
+        ticks += 1;
+    });
+    assert!(ticks > 0);
+}
+
+#[test]
+fn ghost_waits_for_the_player() {
+    let replay = recorded_run(3, 3);
+    let mut ghost = Ghost::new("ada", replay.clone());
+    let start = ghost.world().checksum();
+
+    for _ in 0..100 {
+        assert!(!ghost.tick().unwrap());
+    }
+    assert_eq!(ghost.world().checksum(), start);
+
+    ghost.launch(0).unwrap();
+    assert!(ghost.tick().unwrap());
+    assert_ne!(ghost.world().checksum(), start);
+}
+
+#[test]
+fn ghost_catches_up_and_starts_over() {
+    let replay = recorded_run(3, 4);
+    let mut ghost = Ghost::new("ada", replay.clone());
+
+    let mut world = replay.world();
+    for recorded in &replay.rounds[..2] {
+        world
+            .launch_round(recorded.player, &recorded.moves)
+            .unwrap();
+        while world.tick().0 {}
+        world.spawn_round();
+    }
+
+    ghost.launch(2).unwrap();
+    assert_eq!(ghost.world().checksum(), world.checksum());
+
+    // An undo takes the player back to the first round
+    ghost.launch(0).unwrap();
+    assert_eq!(ghost.world().round, 0);
+    while ghost.tick().unwrap() {}
+    assert_eq!(ghost.world().round, 1);
+
+    // Even while the ghost still plays the undone round
+    let start = replay.world().checksum();
+    ghost.launch(0).unwrap();
+    for _ in 0..10 {
+        ghost.tick().unwrap();
+    }
+    ghost.launch(0).unwrap();
+    assert_eq!(ghost.world().round, 0);
+    assert_eq!(ghost.world().checksum(), start);
+}
+
+#[test]
+fn finished_ghosts_stay_at_rest() {
+    let replay = recorded_run(3, 2);
+    let mut ghost = Ghost::new("ada", replay);
+
+    ghost.launch(5).unwrap();
+    assert_eq!(ghost.world().round, 2);
+    assert!(!ghost.tick().unwrap());
+    assert_eq!(ghost.money_ahead(0), -(ghost.world().money as i64));
+}
@@ -0,0 +1,244 @@
+mod common;
+
+use common::Scene;
+use glam::Vec2;
+use std::collections::BTreeMap;
+use upsi5_core::{
+    entity::{Archetype, Ball, BallId, BallType, EnemyData, PlayerId},
+    events::Event,
+    items::{Item, ItemKind, MAGNET_RADIUS, PowerUps},
+    table::TableRegistry,
+    upgrades::UpgradeLevels,
+    waves::{WaveEvent, WaveScript},
+    world::World,
+};
+
+fn slime(position: Vec2) -> (Vec2, BallType) {
+    (position, BallType::Player(PlayerId(0)))
+}
+
+fn power_up(kind: ItemKind, position: Vec2) -> Item {
+    Item { kind, position }
+}
+
+fn run(world: &mut World, ticks: usize) -> Vec<Event> {
+    (0..ticks).flat_map(|_| world.tick().1).collect()
+}
+
+#[test]
+fn power_ups_are_reported_and_kept_for_later() {
+    let mut world = Scene::new()
+        .balls(&[slime(Vec2::new(0.5, 0.5))])
+        .items(&[
+            power_up(ItemKind::Freeze, Vec2::new(0.51, 0.5)),
+            power_up(ItemKind::TripleSplit, Vec2::new(0.5, 0.51)),
+        ])
+        .build();
+
+    let events = run(&mut world, 1);
+
+    assert_eq!(
+        events,
+        [
+            Event::PowerUpCollected {
+                ball: BallId(100),
+                kind: ItemKind::Freeze,
+                position: Vec2::new(0.51, 0.5),
+            },
+            Event::PowerUpCollected {
+                ball: BallId(100),
+                kind: ItemKind::TripleSplit,
+                position: Vec2::new(0.5, 0.51),
+            },
+        ]
+    );
+    assert!(world.items.is_empty());
+    assert_eq!(world.money, 0);
+    assert_eq!(
+        world.power_ups,
+        [PowerUps {
+            doubled_coins: 0,
+            triple_splits: vec![BallId(100)],
+        }]
+    );
+    assert!(world.frozen);
+}
+
+#[test]
+fn double_coin_doubles_the_next_coin_only() {
+    let mut world = Scene::new()
+        .balls(&[slime(Vec2::new(0.5, 0.5)), slime(Vec2::new(1.5, 0.5))])
+        .items(&[power_up(ItemKind::DoubleCoin, Vec2::new(0.51, 0.5))])
+        .build();
+    run(&mut world, 1);
+    assert_eq!(world.power_ups[0].doubled_coins, 1);
+
+    world.items = vec![
+        Item::coin(Vec2::new(0.51, 0.5)),
+        Item::coin(Vec2::new(1.51, 0.5)),
+    ];
+    let values: Vec<u64> = run(&mut world, 1)
+        .into_iter()
+        .filter_map(|event| match event {
+            Event::CoinCollected { value, .. } => Some(value),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(values, [200, 100]);
+    assert_eq!(world.money, 300);
+    assert_eq!(world.power_ups[0].doubled_coins, 0);
+}
+
+#[test]
+fn magnets_pull_the_nearby_coins() {
+    let near = Vec2::new(0.5 + MAGNET_RADIUS * 0.8, 0.5);
+    let far = Vec2::new(1.5, 0.5);
+    let mut world = Scene::new()
+        .balls(&[slime(Vec2::new(0.5, 0.5))])
+        .items(&[
+            power_up(ItemKind::Magnet, Vec2::new(0.51, 0.5)),
+            Item::coin(near),
+            Item::coin(far),
+        ])
+        .build();
+
+    run(&mut world, 10);
+
+    let coins: Vec<Vec2> = world.items.iter().map(|item| item.position).collect();
+    assert_eq!(coins.len(), 2);
+    assert!(coins[0].x < near.x);
+    assert_eq!(coins[0].y, near.y);
+    assert_eq!(coins[1], far);
+
+    // The pull ends with the round
+    world.spawn_round();
+    let pulled = world.items[0].position;
+    run(&mut world, 10);
+    assert_eq!(world.items[0].position, pulled);
+}
+
+#[test]
+fn freeze_skips_one_timer_countdown() {
+    let enemy = BallType::Enemy(EnemyData::new(Archetype::Basic, 5));
+    let mut world = Scene::new().balls(&[(Vec2::new(1., 0.5), enemy)]).build();
+    let timer = |world: &World| match world.ball(BallId(100)).unwrap().borrow().letypedelaboule {
+        BallType::Enemy(enemy) => enemy.timer,
+        _ => unreachable!(),
+    };
+
+    world.frozen = true;
+    world.spawn_round();
+    assert_eq!(timer(&world), 5);
+    assert!(!world.frozen);
+
+    world.spawn_round();
+    assert_eq!(timer(&world), 4);
+}
+
+#[test]
+fn triple_split_breaks_the_slime_holding_it_in_three() {
+    let mut world = Scene::new()
+        .balls(&[slime(Vec2::new(1., 0.5)), slime(Vec2::new(0.5, 0.2))])
+        .build();
+    world.power_ups[0].triple_splits = vec![BallId(100)];
+    let velocity = Vec2::new(0.1, 0.);
+
+    let pieces = world
+        .launch_round(
+            PlayerId(0),
+            &BTreeMap::from([(BallId(100), velocity), (BallId(101), velocity)]),
+        )
+        .unwrap();
+
+    assert_eq!(pieces[&BallId(101)].len(), 2);
+    let pieces = &pieces[&BallId(100)];
+    assert_eq!(pieces.len(), 3);
+    assert!(world.power_ups[0].triple_splits.is_empty());
+    let balls: Vec<Ball> = pieces
+        .iter()
+        .map(|&id| *world.ball(id).unwrap().borrow())
+        .collect();
+    let mass: f32 = balls.iter().map(|ball| ball.mass).sum();
+    assert!((mass - 0.15).abs() < 1e-6);
+    let momentum: Vec2 = balls.iter().map(|ball| ball.speed).sum();
+    assert!(momentum.length() < 1e-6);
+    assert!(balls[0].speed.normalize().distance(velocity.normalize()) < 1e-6);
+
+    let next = pieces[0];
+    let pieces = world
+        .launch_round(PlayerId(0), &BTreeMap::from([(next, velocity)]))
+        .unwrap();
+    assert_eq!(pieces[&next].len(), 2);
+}
+
+#[test]
+fn authored_and_endless_waves_drop_power_ups() {
+    let script = WaveScript::builtin();
+    let authored = (0..script.authored_rounds())
+        .flat_map(|round| script.wave(round).events)
+        .filter(|event| matches!(event, WaveEvent::PowerUp { .. }))
+        .count();
+    assert!(authored > 0);
+
+    let mut drops: Vec<ItemKind> = (0..40)
+        .flat_map(|depth| script.wave(script.authored_rounds() + depth).events)
+        .filter_map(|event| match event {
+            WaveEvent::PowerUp { item } => Some(item),
+            _ => None,
+        })
+        .collect();
+    drops.sort();
+    drops.dedup();
+    assert_eq!(drops, ItemKind::POWER_UPS);
+}
+
+#[test]
+fn spawned_power_ups_lie_on_free_spots() {
+    let script = WaveScript::builtin();
+    let round = (0..script.authored_rounds())
+        .find(|&round| {
+            script
+                .wave(round)
+                .events
+                .iter()
+                .any(|event| matches!(event, WaveEvent::PowerUp { .. }))
+        })
+        .unwrap();
+
+    let mut world = World::new(
+        7,
+        &UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+    );
+    while world.round < round {
+        world.spawn_round();
+    }
+    world.items.clear();
+    world.spawn_round();
+
+    assert!(world.items.iter().any(|item| item.kind != ItemKind::Coin));
+    for (index, item) in world.items.iter().enumerate() {
+        for other in &world.items[index + 1..] {
+            assert!(item.position.distance(other.position) >= item.radius() + other.radius());
+        }
+    }
+}
+
+#[test]
+fn coins_are_not_power_ups() {
+    let endless = r#""endless": {"first_enemies": 1, "enemies_every": 1, "max_enemies": 1,
+        "timer": 5, "timer_decay_every": 1, "min_timer": 1, "coins": {"min": 1, "max": 1},
+        "coin_rain_every": 0, "coin_rain": 0, "power_up_every": 2, "power_ups": ["freeze"]}"#;
+    let round_coin = format!(
+        r#"{{"rounds": [{{"coins": {{"min": 1, "max": 1}}, "events": [{{"kind": "power_up", "item": "coin"}}]}}], {endless}}}"#
+    );
+    let endless_coin = format!(
+        r#"{{"rounds": [], {}}}"#,
+        endless.replace("[\"freeze\"]", "[\"freeze\", \"coin\"]")
+    );
+
+    assert!(WaveScript::from_json(&format!(r#"{{"rounds": [], {endless}}}"#)).is_ok());
+    assert!(WaveScript::from_json(&round_coin).is_err());
+    assert!(WaveScript::from_json(&endless_coin).is_err());
+}
@@ -0,0 +1,61 @@
+use upsi5_core::leaderboard::{LeaderboardRequest, LeaderboardResponse, Score};
+
+fn score(round: usize, money: u64) -> Score {
+    Score {
+        name: "ada".to_owned(),
+        round,
+        money,
+        seed: 8,
+    }
+}
+
+#[test]
+fn later_rounds_beat_more_money() {
+    assert!(score(6, 0).beats(&score(5, 1000)));
+    assert!(score(5, 200).beats(&score(5, 100)));
+    assert!(!score(5, 100).beats(&score(5, 100)));
+}
+
+#[test]
+fn messages_round_trip() {
+    let requests = [
+        LeaderboardRequest::Submit {
+            score: score(3, 40),
+            replay: vec![1, 2, 3],
+        },
+        LeaderboardRequest::Top { count: 5 },
+        LeaderboardRequest::Rank {
+            name: "ada".to_owned(),
+        },
+        LeaderboardRequest::Replay {
+            name: "ada".to_owned(),
+        },
+    ];
+    for request in requests {
+        assert_eq!(
+            LeaderboardRequest::from_json(&request.to_json()),
+            Ok(request)
+        );
+    }
+
+    let responses = [
+        LeaderboardResponse::Submitted { rank: 2 },
+        LeaderboardResponse::Top {
+            scores: vec![score(3, 40)],
+        },
+        LeaderboardResponse::Rank {
+            rank: None,
+            score: None,
+        },
+        LeaderboardResponse::Replay {
+            score: score(3, 40),
+            replay: vec![4, 5],
+        },
+    ];
+    for response in responses {
+        assert_eq!(
+            LeaderboardResponse::from_json(&response.to_json()),
+            Ok(response)
+        );
+    }
+}
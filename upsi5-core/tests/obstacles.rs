@@ -0,0 +1,151 @@
+mod common;
+
+use common::Scene;
+use glam::Vec2;
+use upsi5_core::{
+    entity::{Ball, BallId, BallType, PlayerId},
+    items::Item,
+    obstacles::Obstacle,
+    world::{ENEMY_BALL_SIZE, World},
+};
+
+fn slime(world: &World) -> Ball {
+    *world.ball(BallId(100)).unwrap().borrow()
+}
+
+#[test]
+fn posts_bounce_balls_back() {
+    let post = Obstacle::Post {
+        center: Vec2::new(0.7, 0.5),
+        radius: 0.03,
+    };
+    let mut world = Scene::new()
+        .moving(&[(
+            Vec2::new(0.5, 0.5),
+            Vec2::new(0.01, 0.),
+            BallType::Player(PlayerId(0)),
+        )])
+        .obstacles(&[post])
+        .build();
+
+    for _ in 0..30 {
+        world.tick();
+        assert!(!post.overlaps(slime(&world).position, ENEMY_BALL_SIZE - 0.001));
+    }
+
+    assert!(slime(&world).speed.x < 0.);
+}
+
+#[test]
+fn fast_balls_dont_go_through_walls() {
+    let wall = Obstacle::Wall {
+        start: Vec2::new(0.6, 0.2),
+        end: Vec2::new(0.6, 0.8),
+    };
+    let mut world = Scene::new()
+        .moving(&[(
+            Vec2::new(0.5, 0.5),
+            Vec2::new(0.09, 0.01),
+            BallType::Player(PlayerId(0)),
+        )])
+        .obstacles(&[wall])
+        .build();
+
+    for _ in 0..20 {
+        world.tick();
+        assert!(slime(&world).position.x < 0.6);
+    }
+}
+
+#[test]
+fn wall_ends_are_round() {
+    let wall = Obstacle::Wall {
+        start: Vec2::new(0.6, 0.2),
+        end: Vec2::new(0.6, 0.4),
+    };
+
+    // The end cap is reached after 0.2 - (0.03 + wall thickness) of the 0.328 move
+    let toi = wall
+        .time_of_impact(Vec2::new(0.6, 0.6), Vec2::new(0., -0.328), 0.03)
+        .unwrap();
+    assert!((toi - 0.5).abs() < 1e-4);
+    assert_eq!(
+        wall.time_of_impact(Vec2::new(0.6, 0.6), Vec2::new(0., 0.328), 0.03),
+        None
+    );
+}
+
+#[test]
+fn bumpers_are_bouncier_than_posts() {
+    let bounce = |obstacle| {
+        let mut world = Scene::new()
+            .moving(&[(
+                Vec2::new(0.5, 0.5),
+                Vec2::new(0.01, 0.),
+                BallType::Player(PlayerId(0)),
+            )])
+            .obstacles(&[obstacle])
+            .build();
+        for _ in 0..30 {
+            world.tick();
+        }
+        slime(&world).speed.length()
+    };
+
+    let post = bounce(Obstacle::Post {
+        center: Vec2::new(0.7, 0.5),
+        radius: 0.03,
+    });
+    let bumper = bounce(Obstacle::Bumper {
+        center: Vec2::new(0.7, 0.5),
+        radius: 0.03,
+        restitution: 1.5,
+    });
+
+    assert!(bumper > 0.01);
+    assert!(bumper > post);
+}
+
+#[test]
+fn nothing_spawns_on_obstacles() {
+    let post = Obstacle::Post {
+        center: Vec2::new(0.963, 0.505),
+        radius: 0.4,
+    };
+    let mut world = Scene::new()
+        .balls(&[(Vec2::new(0.1, 0.1), BallType::Player(PlayerId(0)))])
+        .obstacles(&[post])
+        .build();
+
+    for _ in 0..5 {
+        world.spawn_round();
+    }
+
+    for item in &world.items {
+        assert!(!post.overlaps(item.position, item.radius()));
+    }
+    for ball in &world.balls {
+        let ball = ball.borrow();
+        assert!(!post.overlaps(ball.position, ball.radius));
+    }
+}
+
+#[test]
+fn wave_obstacles_dont_land_on_balls_or_items() {
+    // The round 6 bumper lands right on the slime
+    let mut world = Scene::new()
+        .balls(&[(Vec2::new(0.963, 0.505), BallType::Player(PlayerId(0)))])
+        .build();
+    world.round = 6;
+    world.spawn_round();
+    assert!(world.obstacles.is_empty());
+
+    // Only the second of the round 12 walls is clear of the coin
+    let mut world = Scene::new()
+        .balls(&[(Vec2::new(0.2, 0.2), BallType::Player(PlayerId(0)))])
+        .items(&[Item::coin(Vec2::new(0.55, 0.35))])
+        .build();
+    world.round = 12;
+    world.spawn_round();
+    assert!(matches!(world.obstacles[..], [Obstacle::Wall { start, .. }] if start.x > 1.));
+}
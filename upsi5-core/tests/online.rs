@@ -0,0 +1,145 @@
+use glam::Vec2;
+use std::collections::BTreeMap;
+use upsi5_core::{
+    entity::{BallId, PlayerId},
+    online::{self, ClientMessage, MatchSetup, ServerMessage, Shot},
+    upgrades::UpgradeLevels,
+    world::{LaunchError, World},
+};
+
+fn setup() -> MatchSetup {
+    MatchSetup {
+        seed: 12,
+        levels: UpgradeLevels::default(),
+        table: "classic".to_owned(),
+        players: 2,
+    }
+}
+
+fn slimes(world: &World) -> Vec<BallId> {
+    world
+        .balls
+        .iter()
+        .map(|b| *b.borrow())
+        .filter(|b| b.letypedelaboule.is_player())
+        .map(|b| b.id)
+        .collect()
+}
+
+fn play_round(world: &mut World, moves: &BTreeMap<BallId, Vec2>) {
+    world.launch_round(PlayerId(0), moves).unwrap();
+    while world.tick().0 {}
+    world.spawn_round();
+}
+
+#[test]
+fn shots_keep_the_exact_aim() {
+    let aim = Vec2::new(0.1, -1e-40);
+    let moves = BTreeMap::from([(BallId(3), aim), (BallId(7), Vec2::new(-0.07, 0.13))]);
+
+    let message = ClientMessage::Moves {
+        round: 4,
+        shots: online::shots(&moves),
+    };
+    let ClientMessage::Moves { shots, .. } = ClientMessage::from_json(&message.to_json()).unwrap()
+    else {
+        panic!("expected moves");
+    };
+
+    let decoded = online::moves(&shots);
+    assert_eq!(decoded, moves);
+    assert_eq!(decoded[&BallId(3)].y.to_bits(), Shot::new(BallId(3), aim).y);
+}
+
+#[test]
+fn server_messages_round_trip() {
+    let messages = [
+        ServerMessage::Start {
+            setup: setup(),
+            you: PlayerId(1),
+        },
+        ServerMessage::Round {
+            round: 2,
+            shots: vec![vec![Shot::new(BallId(1), Vec2::new(0.05, 0.))], vec![]],
+        },
+        ServerMessage::Desync { round: 2 },
+        ServerMessage::Left {
+            player: PlayerId(0),
+        },
+        ServerMessage::Error {
+            message: "nope".to_owned(),
+        },
+    ];
+
+    for message in messages {
+        assert_eq!(ServerMessage::from_json(&message.to_json()), Ok(message));
+    }
+    assert!(ServerMessage::from_json(r#"{"type":"dance"}"#).is_err());
+}
+
+#[test]
+fn setups_build_the_same_world_for_everyone() {
+    let replay = setup().replay().unwrap();
+    assert_eq!(replay.players, 2);
+    assert_eq!(replay.world().checksum(), replay.world().checksum());
+
+    let unknown = MatchSetup {
+        table: "moon".to_owned(),
+        ..setup()
+    };
+    assert!(unknown.replay().is_err());
+}
+
+#[test]
+fn checksums_follow_the_simulation() {
+    let replay = setup().replay().unwrap();
+    let mut first = replay.world();
+    let mut second = replay.world();
+
+    let slime = slimes(&first)[0];
+    let moves = BTreeMap::from([(slime, Vec2::new(0.03, 0.1))]);
+    play_round(&mut first, &moves);
+    play_round(&mut second, &moves);
+    assert_eq!(first.checksum(), second.checksum());
+
+    let mut diverged = replay.world();
+    let moves = BTreeMap::from([(slime, Vec2::new(0.03, 0.1001))]);
+    play_round(&mut diverged, &moves);
+    assert_ne!(first.checksum(), diverged.checksum());
+}
+
+#[test]
+fn seats_only_shoot_their_own_slimes() {
+    let replay = setup().replay().unwrap();
+    let mut world = replay.world();
+    let owned = |world: &World, player| {
+        world
+            .balls
+            .iter()
+            .map(|b| *b.borrow())
+            .find(|b| b.letypedelaboule.owner() == Some(player))
+            .unwrap()
+            .id
+    };
+    let first = owned(&world, PlayerId(0));
+    let second = owned(&world, PlayerId(1));
+    let shot = |ball| vec![Shot::new(ball, Vec2::new(0.05, 0.))];
+    let checksum = world.checksum();
+
+    // The second seat shooting the slime of the first one
+    assert_eq!(
+        online::launch_round(&mut world, &[vec![], shot(first)]),
+        Err(LaunchError::NotYours(first))
+    );
+    // Or the same slime as the first one
+    assert_eq!(
+        online::launch_round(&mut world, &[shot(first), shot(first)]),
+        Err(LaunchError::UnknownBall(first))
+    );
+    assert_eq!(world.checksum(), checksum);
+
+    let seats = online::launch_round(&mut world, &[shot(first), shot(second)]).unwrap();
+    assert_eq!(seats.len(), 2);
+    assert_eq!(seats[1].keys().collect::<Vec<_>>(), [&second]);
+    assert_ne!(world.checksum(), checksum);
+}
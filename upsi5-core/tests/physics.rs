@@ -0,0 +1,214 @@
+use glam::Vec2;
+use std::cell::RefCell;
+use upsi5_core::{
+    entity::{Archetype, Ball, BallId, BallType, EnemyData, PlayerId},
+    physics,
+    table::TableRegistry,
+    upgrades::UpgradeLevels,
+    world::World,
+};
+
+const ENEMY: BallType = BallType::Enemy(EnemyData::new(Archetype::Basic, 5));
+
+fn ball(id: u64, mass: f32, position: Vec2, speed: Vec2, letypedelaboule: BallType) -> Ball {
+    Ball::new(BallId(id), mass, position, speed, 1., 0.03, letypedelaboule)
+}
+
+fn momentum(balls: &[Ball]) -> Vec2 {
+    balls.iter().map(|b| b.speed * b.mass).sum()
+}
+
+fn energy(balls: &[Ball]) -> f32 {
+    balls
+        .iter()
+        .map(|b| 0.5 * b.mass * b.speed.length_squared())
+        .sum()
+}
+
+/// Not built with the shared `Scene`: these balls have their own masses, and the same balls are
+/// also collided outside of any world
+fn world_with(balls: &[Ball]) -> World {
+    let mut world = World::new(
+        0,
+        &UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+    );
+    world.balls = balls.iter().copied().map(RefCell::new).collect();
+    world
+}
+
+fn world_balls(world: &World) -> Vec<Ball> {
+    world.balls.iter().map(|b| *b.borrow()).collect()
+}
+
+#[test]
+fn elastic_collision_keeps_momentum_and_energy() {
+    let mut a = ball(0, 0.3, Vec2::new(0.5, 0.5), Vec2::new(0.01, 0.002), ENEMY);
+    let mut b = ball(1, 0.1, Vec2::new(0.555, 0.51), Vec2::new(-0.004, 0.), ENEMY);
+
+    let before = [a, b];
+    let contact = physics::resolve(&mut a, &mut b, 1.).unwrap();
+    let after = [a, b];
+
+    assert!(contact.impulse > 0.);
+    assert!(momentum(&before).distance(momentum(&after)) < 1e-7);
+    assert!((energy(&before) - energy(&after)).abs() < 1e-8);
+}
+
+#[test]
+fn restitution_only_loses_energy() {
+    let mut a = ball(0, 0.15, Vec2::new(0.5, 0.5), Vec2::new(0.02, 0.), ENEMY);
+    let mut b = ball(1, 0.15, Vec2::new(0.55, 0.5), Vec2::ZERO, ENEMY);
+
+    let before = [a, b];
+    physics::resolve(&mut a, &mut b, physics::BALL_RESTITUTION).unwrap();
+    let after = [a, b];
+
+    assert!(momentum(&before).distance(momentum(&after)) < 1e-7);
+    assert!(energy(&after) < energy(&before));
+}
+
+#[test]
+fn separating_balls_get_no_impulse() {
+    let mut a = ball(0, 0.15, Vec2::new(0.5, 0.5), Vec2::new(-0.01, 0.), ENEMY);
+    let mut b = ball(1, 0.15, Vec2::new(0.55, 0.5), Vec2::new(0.01, 0.), ENEMY);
+
+    let contact = physics::resolve(&mut a, &mut b, 1.).unwrap();
+
+    assert_eq!(contact.impulse, 0.);
+    assert_eq!(a.speed, Vec2::new(-0.01, 0.));
+}
+
+#[test]
+fn merge_keeps_momentum() {
+    let a = ball(
+        0,
+        0.2,
+        Vec2::new(0.5, 0.5),
+        Vec2::new(0.01, 0.02),
+        BallType::Player(PlayerId(0)),
+    );
+    let b = ball(
+        1,
+        0.1,
+        Vec2::new(0.55, 0.5),
+        Vec2::new(-0.01, 0.01),
+        BallType::Player(PlayerId(0)),
+    );
+
+    let merged = physics::merge(&a, &b, BallId(2));
+
+    assert!((merged.mass - 0.3).abs() < 1e-7);
+    assert!(momentum(&[a, b]).distance(momentum(&[merged])) < 1e-7);
+}
+
+#[test]
+fn tick_keeps_momentum_and_energy_budget() {
+    let balls = [
+        ball(0, 0.3, Vec2::new(0.6, 0.5), Vec2::new(0.004, 0.001), ENEMY),
+        ball(1, 0.15, Vec2::new(0.8, 0.52), Vec2::new(-0.003, 0.), ENEMY),
+        ball(2, 0.15, Vec2::new(0.7, 0.4), Vec2::new(0., 0.002), ENEMY),
+    ];
+    let mut world = world_with(&balls);
+
+    for _ in 0..40 {
+        world.tick();
+    }
+
+    let after = world_balls(&world);
+    assert!(momentum(&balls).distance(momentum(&after)) < 1e-6);
+    assert!(energy(&after) <= energy(&balls) + 1e-9);
+    assert!(energy(&after) < energy(&balls));
+}
+
+#[test]
+fn fast_ball_does_not_tunnel() {
+    let balls = [
+        ball(0, 0.15, Vec2::new(0.5, 0.5), Vec2::new(0.1, 0.), ENEMY),
+        ball(1, 0.15, Vec2::new(0.6, 0.5), Vec2::ZERO, ENEMY),
+    ];
+    let mut world = world_with(&balls);
+
+    world.tick();
+
+    let after = world_balls(&world);
+    assert!(after[1].speed.x > 0.05);
+    assert!(after[0].position.x < after[1].position.x);
+}
+
+#[test]
+fn tunnelling_balls_are_rewound_along_their_move() {
+    // Friction halves the speed during the tick, the rewind still has to reach the contact point
+    let balls = [
+        Ball::new(
+            BallId(0),
+            0.15,
+            Vec2::new(0.5, 0.5),
+            Vec2::new(0.2, 0.),
+            0.5,
+            0.03,
+            ENEMY,
+        ),
+        Ball::new(
+            BallId(1),
+            0.15,
+            Vec2::new(0.6, 0.5),
+            Vec2::ZERO,
+            0.5,
+            0.03,
+            ENEMY,
+        ),
+    ];
+    let mut world = world_with(&balls);
+
+    world.tick();
+
+    let after = world_balls(&world);
+    assert!(after[1].speed.x > 0.05);
+    assert!(after[0].position.x < after[1].position.x);
+}
+
+#[test]
+fn contacts_are_resolved_in_the_order_they_happen() {
+    // The ball reaches the second one first even though it comes after the third in the world
+    let balls = [
+        ball(0, 0.15, Vec2::new(0.5, 0.5), Vec2::new(0.3, 0.), ENEMY),
+        ball(1, 0.15, Vec2::new(0.75, 0.5), Vec2::ZERO, ENEMY),
+        ball(2, 0.15, Vec2::new(0.6, 0.5), Vec2::ZERO, ENEMY),
+    ];
+    let mut world = world_with(&balls);
+
+    world.tick();
+
+    let after = world_balls(&world);
+    assert!(after[2].speed.x > 0.2);
+    assert_eq!(after[1].speed, Vec2::ZERO);
+    assert_eq!(after[1].position, balls[1].position);
+}
+
+#[test]
+fn slimes_merge_with_their_momentum() {
+    let balls = [
+        ball(
+            0,
+            0.15,
+            Vec2::new(0.5, 0.5),
+            Vec2::new(0.01, 0.01),
+            BallType::Player(PlayerId(0)),
+        ),
+        ball(
+            1,
+            0.15,
+            Vec2::new(0.56, 0.5),
+            Vec2::ZERO,
+            BallType::Player(PlayerId(0)),
+        ),
+    ];
+    let mut world = world_with(&balls);
+
+    world.tick();
+
+    let after = world_balls(&world);
+    assert_eq!(after.len(), 1);
+    assert!(momentum(&balls).distance(momentum(&after)) < 1e-6);
+}
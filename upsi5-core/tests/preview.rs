@@ -0,0 +1,141 @@
+mod common;
+
+use common::Scene;
+use glam::Vec2;
+use std::collections::BTreeMap;
+use upsi5_core::{
+    entity::{Archetype, BallId, BallType, EnemyData, PlayerId},
+    preview::Preview,
+    upgrades::AimAssist,
+    world::{ENEMY_BALL_SIZE, World},
+};
+
+const SLIME: BallId = BallId(100);
+
+fn preview(world: &World, aim: Vec2, assist: AimAssist) -> Preview {
+    Preview::new(
+        world,
+        PlayerId(0),
+        &BTreeMap::from([(SLIME, aim)]),
+        SLIME,
+        assist,
+    )
+    .unwrap()
+}
+
+fn assist(lines: u32, bounces: usize, follow_hits: bool) -> AimAssist {
+    AimAssist {
+        lines,
+        bounces,
+        follow_hits,
+    }
+}
+
+#[test]
+fn world_is_left_untouched() {
+    let world = Scene::new()
+        .friction(0.9995)
+        .balls(&[(Vec2::new(0.5, 0.5), BallType::Player(PlayerId(0)))])
+        .build();
+
+    preview(&world, Vec2::new(-0.15, 0.), assist(2, 3, true));
+
+    assert_eq!(world.balls.len(), 1);
+    let slime = world.ball(SLIME).unwrap().borrow();
+    assert_eq!(slime.position, Vec2::new(0.5, 0.5));
+    assert_eq!(slime.speed, Vec2::ZERO);
+}
+
+#[test]
+fn paths_stop_at_the_first_bounce() {
+    let world = Scene::new()
+        .friction(0.9995)
+        .balls(&[(Vec2::new(0.5, 0.5), BallType::Player(PlayerId(0)))])
+        .build();
+
+    let preview = preview(&world, Vec2::new(-0.15, 0.), assist(1, 0, false));
+
+    let [path] = &preview.paths[..] else {
+        panic!("expected one path, got {:?}", preview.paths);
+    };
+    let end = *path.points.last().unwrap();
+    assert!(end.x < ENEMY_BALL_SIZE);
+    assert!((end.y - 0.5).abs() < 1e-4);
+    assert!(path.points.windows(2).all(|pair| pair[1].x < pair[0].x));
+}
+
+#[test]
+fn more_bounces_go_further() {
+    let world = Scene::new()
+        .friction(0.9995)
+        .balls(&[(Vec2::new(0.5, 0.3), BallType::Player(PlayerId(0)))])
+        .build();
+    let aim = Vec2::new(-0.14, 0.05);
+
+    let short = preview(&world, aim, assist(1, 0, false));
+    let long = preview(&world, aim, assist(1, 2, false));
+
+    let short = &short.paths[0].points;
+    let long = &long.paths[0].points;
+    assert!(long.len() > short.len());
+    assert_eq!(long[..short.len()], short[..]);
+}
+
+#[test]
+fn hit_balls_are_followed_with_the_upgrade() {
+    let world = Scene::new()
+        .friction(0.9995)
+        .balls(&[
+            (Vec2::new(0.5, 0.5), BallType::Player(PlayerId(0))),
+            (
+                Vec2::new(0.3, 0.5),
+                BallType::Enemy(EnemyData::new(Archetype::Basic, 5)),
+            ),
+        ])
+        .build();
+    let aim = Vec2::new(-0.15, 0.);
+
+    let plain = preview(&world, aim, assist(1, 0, false));
+    let followed = preview(&world, aim, assist(1, 0, true));
+
+    assert_eq!(plain.first_hit.map(|hit| hit.0), Some(BallId(101)));
+    assert_eq!(plain.paths.len(), 1);
+    assert_eq!(followed.paths.len(), 2);
+    assert_eq!(followed.paths[1].ball, BallId(101));
+    assert!(followed.paths[1].points.last().unwrap().x < 0.3);
+}
+
+#[test]
+fn pocket_entry_ends_the_path() {
+    let world = Scene::new()
+        .friction(0.9995)
+        .balls(&[(Vec2::new(0.3, 0.5), BallType::Player(PlayerId(0)))])
+        .build();
+    let pocket = world.table().pockets[0].position;
+    let aim = (pocket - Vec2::new(0.3, 0.5)).normalize() * 0.15;
+
+    let preview = preview(&world, aim, assist(2, 0, false));
+
+    assert_eq!(preview.paths[0].pocket, Some(0));
+    assert_eq!(*preview.paths[0].points.last().unwrap(), pocket);
+    assert_eq!(preview.paths[1].pocket, None);
+}
+
+#[test]
+fn hit_splitters_are_not_followed() {
+    let world = Scene::new()
+        .friction(0.9995)
+        .balls(&[
+            (Vec2::new(0.5, 0.5), BallType::Player(PlayerId(0))),
+            (
+                Vec2::new(0.8, 0.5),
+                BallType::Enemy(EnemyData::new(Archetype::Splitter, 5)),
+            ),
+        ])
+        .build();
+
+    let preview = preview(&world, Vec2::new(0.15, 0.), assist(2, 3, true));
+
+    assert_eq!(preview.first_hit.map(|hit| hit.0), Some(BallId(101)));
+    assert!(preview.paths.iter().all(|path| path.ball != BallId(101)));
+}
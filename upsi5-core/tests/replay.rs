@@ -0,0 +1,114 @@
+use glam::Vec2;
+use std::collections::BTreeMap;
+use upsi5_core::{
+    entity::{BallId, BallType, PlayerId},
+    items::Item,
+    replay::{Playback, Replay, ReplayError},
+    table::TableRegistry,
+    upgrades::UpgradeLevels,
+    world::World,
+};
+
+fn player_moves(world: &World, shot: Vec2) -> BTreeMap<BallId, Vec2> {
+    world
+        .balls
+        .iter()
+        .map(|b| *b.borrow())
+        .filter(|b| b.letypedelaboule == BallType::Player(PlayerId(0)))
+        .map(|b| (b.id, shot))
+        .collect()
+}
+
+/// Play a few rounds like the game does and record them
+fn live_run(seed: u64) -> (World, Replay) {
+    let mut levels = UpgradeLevels::default();
+    levels.set("max_speed", 2);
+    levels.set("profitability", 1);
+    levels.set("start_mass", 1);
+
+    let mut replay = Replay::new(seed, levels, TableRegistry::builtin().default_table());
+    let mut world = replay.world();
+
+    for shot in [
+        Vec2::new(0.12, 0.02),
+        Vec2::new(-0.03, 0.1),
+        Vec2::new(0.07, -0.09),
+    ] {
+        if world.is_game_over() {
+            break;
+        }
+        let moves = player_moves(&world, shot);
+        world.launch_round(PlayerId(0), &moves).unwrap();
+        replay.record(world.round, PlayerId(0), &moves);
+        while world.tick().0 {}
+        world.spawn_round();
+    }
+
+    (world, replay)
+}
+
+fn snapshot(world: &World) -> (Vec<(u64, Vec2)>, Vec<Item>, u64, usize) {
+    (
+        world
+            .balls
+            .iter()
+            .map(|b| (b.borrow().id.0, b.borrow().position))
+            .collect(),
+        world.items.clone(),
+        world.money,
+        world.round,
+    )
+}
+
+#[test]
+fn bytes_round_trip() {
+    let (_, replay) = live_run(11);
+
+    assert_eq!(Replay::from_bytes(&replay.to_bytes()), Ok(replay));
+}
+
+#[test]
+fn garbage_is_rejected() {
+    let (_, replay) = live_run(11);
+    let bytes = replay.to_bytes();
+
+    assert_eq!(Replay::from_bytes(b"nope"), Err(ReplayError::Format));
+    assert_eq!(
+        Replay::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(ReplayError::Format)
+    );
+}
+
+#[test]
+fn playback_matches_live_run() {
+    let (live, replay) = live_run(5);
+
+    let replayed = Playback::new(replay).run_to_end().unwrap();
+
+    assert_eq!(snapshot(&live), snapshot(&replayed));
+}
+
+#[test]
+fn desync_is_reported() {
+    let (_, mut replay) = live_run(5);
+    replay.rounds[0].round = 3;
+
+    assert!(matches!(
+        Playback::new(replay).run_to_end(),
+        Err(ReplayError::Desync {
+            expected: 3,
+            found: 0
+        })
+    ));
+}
+
+#[test]
+fn table_is_recorded() {
+    let table = TableRegistry::builtin().get("long").unwrap();
+    let replay = Replay::new(5, UpgradeLevels::default(), table);
+
+    let decoded = Replay::from_bytes(&replay.to_bytes()).unwrap();
+
+    assert_eq!(decoded.table.id, "long");
+    assert_eq!(decoded.world().table().dimensions, table.dimensions);
+}
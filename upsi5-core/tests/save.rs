@@ -0,0 +1,155 @@
+use upsi5_core::{
+    achievements::AchievementProgress,
+    daily::{DailyResult, Date},
+    save::{SAVE_VERSION, SaveData, SaveError},
+    upgrades::UpgradeLevels,
+};
+
+fn progress() -> SaveData {
+    let mut levels = UpgradeLevels::default();
+    levels.set("max_speed", 2);
+    levels.set("start_mass", 1);
+    levels.set("aim_assist", 3);
+    levels.set("sliding", 4);
+
+    SaveData {
+        total_money: 12_345,
+        best_round: 17,
+        levels,
+        daily: None,
+        last_run: None,
+        achievements: AchievementProgress::default(),
+    }
+}
+
+#[test]
+fn round_trip() {
+    let save = progress();
+
+    assert_eq!(SaveData::from_json(&save.to_json()), Ok(save));
+}
+
+#[test]
+fn save_is_versioned() {
+    let json: serde_json::Value = serde_json::from_str(&progress().to_json()).unwrap();
+
+    assert_eq!(json["version"], SAVE_VERSION);
+}
+
+#[test]
+fn newer_saves_are_rejected() {
+    let json = progress()
+        .to_json()
+        .replace(&format!("\"version\":{SAVE_VERSION}"), "\"version\":999");
+
+    assert_eq!(
+        SaveData::from_json(&json),
+        Err(SaveError::UnsupportedVersion(999))
+    );
+}
+
+#[test]
+fn garbage_is_rejected() {
+    assert!(matches!(
+        SaveData::from_json("not a save"),
+        Err(SaveError::Format(_))
+    ));
+    assert!(matches!(
+        SaveData::from_json("{}"),
+        Err(SaveError::Format(_))
+    ));
+}
+
+#[test]
+fn version_1_saves_are_migrated() {
+    let json = r#"{"version":1,"total_money":12345,"best_round":17,"max_speed_level":2,"start_mass_level":1,"aim_assist_level":3,"profitability_level":0,"sliding_level":4}"#;
+
+    let mut expected = progress();
+    expected.levels.set("profitability", 0);
+
+    assert_eq!(SaveData::from_json(json), Ok(expected));
+}
+
+#[test]
+fn daily_attempt_round_trips() {
+    let save = SaveData {
+        daily: Some(DailyResult {
+            date: Date {
+                year: 2026,
+                month: 10,
+                day: 18,
+            },
+            round: 9,
+            money: 1_200,
+        }),
+        ..progress()
+    };
+
+    assert_eq!(SaveData::from_json(&save.to_json()), Ok(save));
+}
+
+#[test]
+fn version_2_saves_are_migrated() {
+    let json = r#"{"version":2,"total_money":12345,"best_round":17,"levels":{"max_speed":2,"start_mass":1,"aim_assist":3,"sliding":4}}"#;
+
+    assert_eq!(SaveData::from_json(json), Ok(progress()));
+}
+
+#[test]
+fn version_3_saves_are_migrated() {
+    let json = r#"{"version":3,"total_money":12345,"best_round":17,"levels":{"max_speed":2,"start_mass":1,"aim_assist":3,"sliding":4},"daily":null}"#;
+
+    assert_eq!(SaveData::from_json(json), Ok(progress()));
+}
+
+#[test]
+fn last_run_round_trips() {
+    let save = SaveData {
+        last_run: Some(vec![b'S', b'P', b'R', b'P', 4, 0, 255]),
+        ..progress()
+    };
+
+    assert_eq!(SaveData::from_json(&save.to_json()), Ok(save));
+}
+
+#[test]
+fn version_4_saves_are_migrated() {
+    let json = r#"{"version":4,"total_money":12345,"best_round":17,"levels":{"max_speed":2,"start_mass":1,"aim_assist":3,"sliding":4},"daily":null,"last_run":null}"#;
+
+    assert_eq!(SaveData::from_json(json), Ok(progress()));
+}
+
+#[test]
+fn last_run_is_written_as_text() {
+    let save = SaveData {
+        last_run: Some(vec![b'S', b'P', b'R', b'P', 4, 0, 255]),
+        ..progress()
+    };
+    let json: serde_json::Value = serde_json::from_str(&save.to_json()).unwrap();
+
+    assert_eq!(json["last_run"], "U1BSUAQA/w==");
+}
+
+#[test]
+fn version_5_saves_are_migrated() {
+    let json = r#"{"version":5,"total_money":12345,"best_round":17,"levels":{"max_speed":2,"start_mass":1,"aim_assist":3,"sliding":4},"daily":null,"last_run":[83,80,82,80,4,0,255],"achievements":{"unlocked":[],"counters":{}}}"#;
+
+    assert_eq!(
+        SaveData::from_json(json),
+        Ok(SaveData {
+            last_run: Some(vec![b'S', b'P', b'R', b'P', 4, 0, 255]),
+            ..progress()
+        })
+    );
+}
+
+#[test]
+fn achievements_round_trip() {
+    let mut save = progress();
+    save.achievements.unlocked.insert("hat_trick".to_owned());
+    save.achievements
+        .counters
+        .insert("hoarder".to_owned(), 1_234);
+
+    assert_eq!(SaveData::from_json(&save.to_json()), Ok(save));
+}
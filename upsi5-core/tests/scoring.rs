@@ -0,0 +1,161 @@
+use glam::Vec2;
+use upsi5_core::{
+    balance::{Policy, RandomPolicy},
+    entity::{Archetype, BallId, BallType, EnemyData, PlayerId},
+    events::Event,
+    scoring::{Combo, ComboKind, RoundScore},
+    table::TableRegistry,
+    upgrades::UpgradeLevels,
+    world::World,
+};
+
+fn coin(ball: u64) -> Event {
+    Event::CoinCollected {
+        ball: BallId(ball),
+        position: Vec2::new(0.5, 0.5),
+        value: 100,
+    }
+}
+
+fn border(ball: u64) -> Event {
+    Event::Bounce {
+        ball: BallId(ball),
+        obstacle: None,
+        point: Vec2::ZERO,
+        impulse: 0.001,
+    }
+}
+
+fn enemy_pocketed(ball: u64) -> Event {
+    Event::Pocketed {
+        ball: BallId(ball),
+        kind: BallType::Enemy(EnemyData::new(Archetype::Basic, 5)),
+        pocket: 0,
+    }
+}
+
+fn kinds(combos: &[Event]) -> Vec<(ComboKind, u64)> {
+    combos
+        .iter()
+        .map(|event| match *event {
+            Event::Combo {
+                kind, multiplier, ..
+            } => (kind, multiplier),
+            _ => panic!("not a combo: {event:?}"),
+        })
+        .collect()
+}
+
+#[test]
+fn combos_raise_the_multiplier() {
+    let table = TableRegistry::builtin().default_table();
+    let mut combo = Combo::default();
+    combo.start(&[0]);
+
+    assert!(combo.record(&[coin(1)], table).is_empty());
+    assert_eq!(
+        kinds(&combo.record(&[coin(2)], table)),
+        [(ComboKind::Collector, 2)]
+    );
+    // Bank shots need a bounce off the border first, and count once per ball
+    assert!(combo.record(&[coin(1)], table).is_empty());
+    assert_eq!(
+        kinds(&combo.record(&[border(2), coin(2)], table)),
+        [(ComboKind::BankShot, 3)]
+    );
+    assert!(combo.record(&[coin(2)], table).is_empty());
+    // The first pocket is no combo yet
+    assert!(combo.record(&[enemy_pocketed(7)], table).is_empty());
+    assert_eq!(
+        kinds(&combo.record(&[border(8), enemy_pocketed(8)], table)),
+        [(ComboKind::Pocket, 4), (ComboKind::BankShot, 5)]
+    );
+
+    let (score, bonuses) = combo.finish(&[500]);
+    assert_eq!(
+        score,
+        RoundScore {
+            coins: 500,
+            pockets: 2,
+            collectors: 2,
+            bank_shots: 2,
+            multiplier: 5,
+            bonus: 2000,
+        }
+    );
+    assert_eq!(bonuses, [2000]);
+    assert!(!combo.is_active());
+}
+
+#[test]
+fn bonuses_follow_the_money_of_each_player() {
+    let table = TableRegistry::builtin().default_table();
+    let mut combo = Combo::default();
+    combo.start(&[1000, 200]);
+
+    combo.record(&[coin(1), coin(2), coin(3)], table);
+    let (score, bonuses) = combo.finish(&[1200, 300]);
+
+    assert_eq!(score.multiplier, 3);
+    assert_eq!(bonuses, [400, 200]);
+    assert_eq!(score.bonus, 600);
+}
+
+#[test]
+fn rounds_not_launched_score_nothing() {
+    let table = TableRegistry::builtin().default_table();
+    let mut combo = Combo::default();
+
+    assert!(combo.record(&[coin(1), coin(2)], table).is_empty());
+    assert_eq!(combo.score(), RoundScore::default());
+}
+
+#[test]
+fn every_round_is_scored_once_before_spawning() {
+    let mut world = World::new(
+        3,
+        &UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+    );
+    let mut policy = RandomPolicy::new(3);
+
+    for _ in 0..8 {
+        if world.is_game_over() {
+            break;
+        }
+        let money = world.money;
+        let moves = policy.moves(&world);
+        world.launch_round(PlayerId(0), &moves).unwrap();
+
+        let mut coins = 0;
+        let mut scores = Vec::new();
+        loop {
+            let (run, events) = world.tick();
+            for event in events {
+                match event {
+                    Event::CoinCollected { value, .. } => coins += value,
+                    Event::RoundScored { score } => scores.push(score),
+                    _ => {}
+                }
+            }
+            if !run {
+                break;
+            }
+        }
+
+        assert_eq!(scores.len(), 1);
+        assert_eq!(scores[0].coins, coins);
+        assert_eq!(world.money, money + coins + scores[0].bonus);
+        assert_eq!(world.round_score().multiplier, scores[0].multiplier);
+
+        // Ticks at rest don't score the round again
+        assert!(
+            !world
+                .tick()
+                .1
+                .iter()
+                .any(|event| matches!(event, Event::RoundScored { .. }))
+        );
+        world.spawn_round();
+    }
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use common::Scene;
+use glam::Vec2;
+use std::{collections::BTreeMap, task::Poll};
+use upsi5_core::{
+    entity::{Archetype, BallId, BallType, EnemyData, PlayerId},
+    solver::Solver,
+    table::TableRegistry,
+    upgrades::UpgradeLevels,
+    world::World,
+};
+
+fn enemy(timer: usize) -> BallType {
+    BallType::Enemy(EnemyData::new(Archetype::Basic, timer))
+}
+
+#[test]
+fn lined_up_enemies_get_pocketed() {
+    let world = Scene::new()
+        .friction(0.9994)
+        .balls(&[
+            (Vec2::new(0.3, 0.3), BallType::Player(PlayerId(0))),
+            (Vec2::new(0.15, 0.15), enemy(5)),
+        ])
+        .build();
+
+    let solution = Solver::default().solve(&world, PlayerId(0)).unwrap();
+
+    assert_eq!(solution.moves.keys().collect::<Vec<_>>(), [&BallId(100)]);
+    assert!(solution.outcome.enemies_pocketed >= 1);
+    assert!(!solution.outcome.game_over);
+    assert_eq!(world.balls.len(), 2);
+}
+
+#[test]
+fn expiring_timers_are_deadly() {
+    let world = Scene::new()
+        .friction(0.9994)
+        .balls(&[
+            (Vec2::new(0.5, 0.5), BallType::Player(PlayerId(0))),
+            (Vec2::new(1.5, 0.5), enemy(1)),
+        ])
+        .build();
+
+    let outcome = Solver::simulate(
+        &world,
+        PlayerId(0),
+        &BTreeMap::from([(BallId(100), Vec2::new(0., 0.05))]),
+    );
+
+    assert!(outcome.game_over);
+    assert!(outcome.score() < 0.);
+}
+
+#[test]
+fn falling_slimes_lose_score() {
+    let world = Scene::new()
+        .friction(0.9994)
+        .balls(&[(Vec2::new(0.3, 0.5), BallType::Player(PlayerId(0)))])
+        .build();
+    let pocket = world.table().pockets[0].position;
+    let aim = (pocket - Vec2::new(0.3, 0.5)).normalize() * 0.15;
+
+    let outcome = Solver::simulate(&world, PlayerId(0), &BTreeMap::from([(BallId(100), aim)]));
+
+    assert!((outcome.mass_lost - 0.075).abs() < 1e-6);
+    assert!(!outcome.game_over);
+    assert!(outcome.score() < 0.);
+}
+
+#[test]
+fn no_slime_no_solution() {
+    let world = Scene::new()
+        .friction(0.9994)
+        .balls(&[(Vec2::new(0.5, 0.5), enemy(5))])
+        .build();
+
+    assert_eq!(Solver::hint().solve(&world, PlayerId(0)), None);
+}
+
+#[test]
+fn hints_are_deterministic() {
+    let world = World::new(
+        3,
+        &UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+    );
+
+    assert_eq!(
+        Solver::hint().solve(&world, PlayerId(0)),
+        Solver::hint().solve(&world, PlayerId(0))
+    );
+}
+
+#[test]
+fn searches_stepped_by_frames_find_the_same_shot() {
+    let world = Scene::new()
+        .friction(0.9994)
+        .balls(&[
+            (Vec2::new(0.3, 0.3), BallType::Player(PlayerId(0))),
+            (Vec2::new(0.6, 0.7), BallType::Player(PlayerId(0))),
+            (Vec2::new(0.15, 0.15), enemy(5)),
+        ])
+        .build();
+    let solver = Solver::hint();
+
+    let mut search = solver.search(&world, PlayerId(0));
+    let mut frames = 1;
+    let found = loop {
+        match search.step(500) {
+            Poll::Ready(found) => break found,
+            Poll::Pending => frames += 1,
+        }
+    };
+
+    assert!(frames > 1);
+    assert_eq!(found, solver.solve(&world, PlayerId(0)));
+}
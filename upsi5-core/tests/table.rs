@@ -0,0 +1,134 @@
+use glam::Vec2;
+use upsi5_core::{
+    entity::{BallType, PlayerId},
+    obstacles::Obstacle,
+    table::{Table, TableRegistry},
+    upgrades::UpgradeLevels,
+    world::World,
+};
+
+fn assert_on_table(world: &World, table: &Table) {
+    for ball in &world.balls {
+        let ball = ball.borrow();
+        assert!(
+            table.contains(ball.position),
+            "{} ball off the table",
+            table.id
+        );
+        for pocket in &table.pockets {
+            assert!(ball.position.distance(pocket.position) >= pocket.radius);
+        }
+    }
+    for item in &world.items {
+        assert!(
+            table.contains(item.position),
+            "{} item off the table",
+            table.id
+        );
+    }
+}
+
+#[test]
+fn builtin_tables_are_valid() {
+    let tables = TableRegistry::builtin();
+
+    assert_eq!(tables.default_table().id, "classic");
+    for id in ["classic", "small", "long", "six_pocket"] {
+        assert!(tables.get(id).is_some(), "missing {id}");
+    }
+    assert_eq!(tables.get("six_pocket").unwrap().pockets.len(), 6);
+}
+
+#[test]
+fn formation_is_placed() {
+    for table in TableRegistry::builtin().iter() {
+        let world = World::new(0, &UpgradeLevels::default(), table);
+
+        assert_eq!(world.balls.len(), table.formation.len());
+        assert_eq!(
+            world
+                .balls
+                .iter()
+                .filter(|b| b.borrow().letypedelaboule == BallType::Player(PlayerId(0)))
+                .count(),
+            1
+        );
+        assert_on_table(&world, table);
+    }
+}
+
+#[test]
+fn rounds_stay_on_the_table() {
+    for table in TableRegistry::builtin().iter() {
+        let mut world = World::new(9, &UpgradeLevels::default(), table);
+
+        while !world.is_game_over() {
+            world.spawn_round();
+        }
+
+        assert_on_table(&world, table);
+        for obstacle in &world.obstacles {
+            assert!(table.holds(obstacle));
+        }
+    }
+}
+
+#[test]
+fn obstacles_over_pockets_arent_held() {
+    let table = TableRegistry::builtin().default_table();
+    let pocket = table.pockets[2];
+    let post = |center: Vec2| Obstacle::Post {
+        center,
+        radius: 0.025,
+    };
+
+    assert!(table.holds(&post(pocket.position + Vec2::new(0., 0.1))));
+    assert!(!table.holds(&post(pocket.position + Vec2::new(0., 0.05))));
+    assert!(!table.holds(&Obstacle::Wall {
+        start: pocket.position + Vec2::new(-0.1, 0.),
+        end: pocket.position + Vec2::new(0.1, 0.),
+    }));
+}
+
+#[test]
+fn wave_obstacles_follow_the_table_size() {
+    for table in TableRegistry::builtin().iter() {
+        let mut world = World::new(0, &UpgradeLevels::default(), table);
+        world.balls.clear();
+        world.items.clear();
+        world.round = 12;
+        world.spawn_round();
+
+        let Obstacle::Wall { start, .. } = world.obstacles[0] else {
+            panic!("{} lost the round 12 walls", table.id);
+        };
+        assert!((start / table.dimensions).distance(Vec2::new(0.2856, 0.2475)) < 1e-5);
+    }
+}
+
+#[test]
+fn invalid_tables_are_rejected() {
+    let table = |pocket: &str, formation: &str| {
+        format!(
+            r#"[{{"id": "t", "name": "T", "dimensions": [1, 1], "border": 0.1,
+                "background": "b", "pockets": [{pocket}], "formation": [{formation}]}}]"#
+        )
+    };
+    let pocket = r#"{"position": [0.5, 0.5], "radius": 0.1}"#;
+    let slime = r#"{"kind": "slime", "position": [0.5, 0.5]}"#;
+
+    assert!(TableRegistry::from_json(&table(pocket, slime)).is_ok());
+    for json in [
+        table(r#"{"position": [2, 0.5], "radius": 0.1}"#, slime),
+        table(pocket, r#"{"kind": "enemy", "position": [0.5, 0.5]}"#),
+        table(pocket, r#"{"kind": "slime", "position": [0.5, -1]}"#),
+        table(
+            pocket,
+            r#"{"kind": "slime", "position": [0.5, 0.5]},
+               {"kind": "enemy", "position": [0.2, 0.2], "timer": 0}"#,
+        ),
+        "[]".to_owned(),
+    ] {
+        assert!(TableRegistry::from_json(&json).is_err(), "{json}");
+    }
+}
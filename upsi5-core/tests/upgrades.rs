@@ -0,0 +1,63 @@
+use upsi5_core::upgrades::{Stats, UpgradeLevels, UpgradeRegistry};
+
+#[test]
+fn builtin_catalogue_is_valid() {
+    let registry = UpgradeRegistry::builtin();
+
+    for id in [
+        "aim_assist",
+        "max_speed",
+        "profitability",
+        "start_mass",
+        "sliding",
+        "undo",
+    ] {
+        assert!(registry.get(id).is_some(), "missing {id}");
+    }
+}
+
+#[test]
+fn level_zero_matches_defaults() {
+    let stats = UpgradeRegistry::builtin().stats(&UpgradeLevels::default());
+
+    assert_eq!(stats, Stats::default());
+}
+
+#[test]
+fn levels_are_clamped_to_max() {
+    let registry = UpgradeRegistry::builtin();
+    let upgrade = registry.get("max_speed").unwrap();
+
+    let mut maxed = UpgradeLevels::default();
+    maxed.set("max_speed", upgrade.max_level);
+    let mut over = UpgradeLevels::default();
+    over.set("max_speed", upgrade.max_level + 10);
+
+    assert_eq!(registry.stats(&maxed), registry.stats(&over));
+    assert_eq!(upgrade.price(upgrade.max_level), None);
+}
+
+#[test]
+fn invalid_catalogues_are_rejected() {
+    let missing_price = r#"[{"id":"a","icon":"x","max_level":2,"prices":[1],
+        "effect":{"kind":"max_speed","values":[1,2,3]}}]"#;
+    let missing_value = r#"[{"id":"a","icon":"x","max_level":1,"prices":[1],
+        "effect":{"kind":"max_speed","values":[1]}}]"#;
+    let duplicate = r#"[
+        {"id":"a","icon":"x","max_level":0,"prices":[],"effect":{"kind":"friction","values":[1]}},
+        {"id":"a","icon":"x","max_level":0,"prices":[],"effect":{"kind":"friction","values":[1]}}
+    ]"#;
+
+    for json in [missing_price, missing_value, duplicate, "not json"] {
+        assert!(UpgradeRegistry::from_json(json).is_err());
+    }
+}
+
+#[test]
+fn undo_upgrade_adds_undos() {
+    let registry = UpgradeRegistry::builtin();
+    let mut levels = UpgradeLevels::default();
+    levels.set("undo", 2);
+
+    assert_eq!(registry.stats(&levels).undos, Stats::default().undos + 2);
+}
@@ -0,0 +1,309 @@
+mod common;
+
+use common::Scene;
+use glam::Vec2;
+use std::collections::BTreeMap;
+use upsi5_core::{
+    entity::{BallId, BallType, PlayerId},
+    events::Event,
+    items::{Item, ItemKind},
+    replay::{Playback, Replay, ReplayError},
+    solver::Solver,
+    table::TableRegistry,
+    upgrades::UpgradeLevels,
+    world::{LaunchError, World},
+};
+
+fn run(world: &mut World, ticks: usize) -> Vec<Event> {
+    (0..ticks).flat_map(|_| world.tick().1).collect()
+}
+
+fn slimes_of(world: &World, player: PlayerId) -> Vec<BallId> {
+    world
+        .balls
+        .iter()
+        .map(|b| *b.borrow())
+        .filter(|b| b.letypedelaboule == BallType::Player(player))
+        .map(|b| b.id)
+        .collect()
+}
+
+#[test]
+fn each_player_starts_with_their_own_slime() {
+    let world = World::with_players(
+        0,
+        &UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+        2,
+    );
+
+    assert_eq!(world.players(), 2);
+    assert_eq!(world.player_money, [0, 0]);
+
+    let first = slimes_of(&world, PlayerId(0));
+    let second = slimes_of(&world, PlayerId(1));
+    assert_eq!(first.len(), 1);
+    assert_eq!(second.len(), 1);
+
+    let position = |id| world.ball(id).unwrap().borrow().position;
+    assert!(position(first[0]).distance(position(second[0])) > 0.2);
+}
+
+#[test]
+fn slimes_of_different_players_bounce() {
+    let mut world = Scene::new()
+        .players(2)
+        .moving(&[
+            (
+                Vec2::new(0.5, 0.5),
+                Vec2::new(0.01, 0.),
+                BallType::Player(PlayerId(0)),
+            ),
+            (
+                Vec2::new(0.6, 0.5),
+                Vec2::ZERO,
+                BallType::Player(PlayerId(1)),
+            ),
+        ])
+        .build();
+
+    let events = run(&mut world, 30);
+
+    assert!(
+        events
+            .iter()
+            .any(|event| matches!(event, Event::Collision { .. }))
+    );
+    assert!(
+        !events
+            .iter()
+            .any(|event| matches!(event, Event::Merged { .. }))
+    );
+    assert_eq!(world.balls.len(), 2);
+    assert!(world.ball(BallId(101)).unwrap().borrow().speed.x > 0.);
+}
+
+#[test]
+fn slimes_of_the_same_player_merge() {
+    let mut world = Scene::new()
+        .players(2)
+        .moving(&[
+            (
+                Vec2::new(0.5, 0.5),
+                Vec2::new(0.01, 0.),
+                BallType::Player(PlayerId(1)),
+            ),
+            (
+                Vec2::new(0.6, 0.5),
+                Vec2::ZERO,
+                BallType::Player(PlayerId(1)),
+            ),
+        ])
+        .build();
+
+    let events = run(&mut world, 30);
+
+    assert!(
+        events
+            .iter()
+            .any(|event| matches!(event, Event::Merged { .. }))
+    );
+    assert_eq!(world.balls.len(), 1);
+    assert_eq!(
+        world.balls[0].borrow().letypedelaboule,
+        BallType::Player(PlayerId(1))
+    );
+}
+
+#[test]
+fn coins_go_to_the_player_picking_them() {
+    let mut world = Scene::new()
+        .players(2)
+        .balls(&[
+            (Vec2::new(0.5, 0.5), BallType::Player(PlayerId(0))),
+            (Vec2::new(1.5, 0.5), BallType::Player(PlayerId(1))),
+        ])
+        .items(&[Item::coin(Vec2::new(1.51, 0.5))])
+        .build();
+
+    run(&mut world, 1);
+
+    assert_eq!(world.player_money, [0, world.money]);
+    assert!(world.money > 0);
+}
+
+#[test]
+fn power_ups_go_to_the_player_picking_them() {
+    let mut world = Scene::new()
+        .players(2)
+        .balls(&[
+            (Vec2::new(0.5, 0.5), BallType::Player(PlayerId(0))),
+            (Vec2::new(1.5, 0.5), BallType::Player(PlayerId(1))),
+        ])
+        .items(&[
+            Item {
+                kind: ItemKind::DoubleCoin,
+                position: Vec2::new(0.51, 0.5),
+            },
+            Item {
+                kind: ItemKind::TripleSplit,
+                position: Vec2::new(1.51, 0.5),
+            },
+        ])
+        .build();
+    run(&mut world, 1);
+
+    assert_eq!(world.power_ups[0].doubled_coins, 1);
+    assert_eq!(world.power_ups[1].triple_splits, [BallId(101)]);
+
+    // The second player's coin isn't doubled by the first player's power-up
+    world.items = vec![Item::coin(Vec2::new(1.51, 0.5))];
+    run(&mut world, 1);
+    assert_eq!(world.player_money[1], world.money);
+    assert_eq!(world.power_ups[0].doubled_coins, 1);
+
+    let velocity = Vec2::new(0.1, 0.);
+    let pieces = world
+        .launch_round(PlayerId(0), &BTreeMap::from([(BallId(100), velocity)]))
+        .unwrap();
+    assert_eq!(pieces[&BallId(100)].len(), 2);
+    let pieces = world
+        .launch_round(PlayerId(1), &BTreeMap::from([(BallId(101), velocity)]))
+        .unwrap();
+    assert_eq!(pieces[&BallId(101)].len(), 3);
+}
+
+#[test]
+fn merged_slimes_keep_their_triple_split() {
+    let mut world = Scene::new()
+        .players(2)
+        .moving(&[
+            (
+                Vec2::new(0.5, 0.5),
+                Vec2::new(0.01, 0.),
+                BallType::Player(PlayerId(1)),
+            ),
+            (
+                Vec2::new(0.6, 0.5),
+                Vec2::ZERO,
+                BallType::Player(PlayerId(1)),
+            ),
+        ])
+        .build();
+    world.power_ups[1].triple_splits = vec![BallId(101)];
+
+    run(&mut world, 30);
+
+    assert_eq!(world.balls.len(), 1);
+    let merged = world.balls[0].borrow().id;
+    assert_eq!(world.power_ups[1].triple_splits, [merged]);
+}
+
+#[test]
+fn players_only_launch_their_own_slimes() {
+    let mut world = World::with_players(
+        0,
+        &UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+        2,
+    );
+    let first = slimes_of(&world, PlayerId(0))[0];
+    let second = slimes_of(&world, PlayerId(1))[0];
+    let checksum = world.checksum();
+
+    let both = BTreeMap::from([(first, Vec2::new(0.05, 0.)), (second, Vec2::new(0.05, 0.))]);
+    assert_eq!(
+        world.launch_round(PlayerId(0), &both),
+        Err(LaunchError::NotYours(second))
+    );
+    assert_eq!(
+        world.launch_round(PlayerId(1), &both),
+        Err(LaunchError::NotYours(first))
+    );
+    assert_eq!(world.checksum(), checksum);
+
+    let pieces = world
+        .launch_round(
+            PlayerId(1),
+            &BTreeMap::from([(second, Vec2::new(0.05, 0.))]),
+        )
+        .unwrap();
+    assert_eq!(pieces.keys().collect::<Vec<_>>(), [&second]);
+}
+
+#[test]
+fn replays_shooting_the_other_players_slimes_are_rejected() {
+    let mut replay = Replay::with_players(
+        9,
+        UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+        2,
+    );
+    let world = replay.world();
+    let second = slimes_of(&world, PlayerId(1))[0];
+    replay.record(
+        0,
+        PlayerId(0),
+        &BTreeMap::from([(second, Vec2::new(0.05, 0.))]),
+    );
+
+    let loaded = Replay::from_bytes(&replay.to_bytes()).unwrap();
+    assert_eq!(
+        Playback::new(loaded).run_to_end().err(),
+        Some(ReplayError::Launch(LaunchError::NotYours(second)))
+    );
+}
+
+#[test]
+fn solver_only_shoots_the_slimes_of_the_player() {
+    let world = World::with_players(
+        4,
+        &UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+        2,
+    );
+    let second = slimes_of(&world, PlayerId(1));
+
+    let solution = Solver::hint().solve(&world, PlayerId(1)).unwrap();
+
+    assert!(solution.moves.keys().all(|id| second.contains(id)));
+}
+
+#[test]
+fn versus_replays_play_back_both_players() {
+    let mut replay = Replay::with_players(
+        9,
+        UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+        2,
+    );
+    let mut world = replay.world();
+
+    for (round, player) in [PlayerId(0), PlayerId(1), PlayerId(0)]
+        .into_iter()
+        .enumerate()
+    {
+        if world.is_game_over() {
+            break;
+        }
+        let moves: BTreeMap<BallId, Vec2> = slimes_of(&world, player)
+            .into_iter()
+            .map(|id| (id, Vec2::new(0.02, 0.1 - round as f32 * 0.05)))
+            .collect();
+        world.launch_round(player, &moves).unwrap();
+        replay.record(world.round, player, &moves);
+        while world.tick().0 {}
+        world.spawn_round();
+    }
+
+    let loaded = Replay::from_bytes(&replay.to_bytes()).unwrap();
+    assert_eq!(loaded, replay);
+    assert_eq!(loaded.players, 2);
+
+    let mut playback = Playback::new(loaded);
+    while !playback.is_finished() {
+        playback.tick().unwrap();
+    }
+    assert_eq!(playback.world.player_money, world.player_money);
+    assert_eq!(playback.world.round, world.round);
+}
@@ -0,0 +1,95 @@
+use upsi5_core::{
+    table::TableRegistry,
+    upgrades::UpgradeLevels,
+    waves::{WaveEvent, WaveScript},
+    world::World,
+};
+
+#[test]
+fn authored_rounds_come_first() {
+    let script = WaveScript::builtin();
+    let counts = [1, 0, 1, 0, 1, 2, 1, 0, 3, 1, 0, 2, 0, 1, 2, 0, 1, 2, 3, 1];
+
+    assert_eq!(script.authored_rounds(), counts.len());
+    for (round, count) in counts.into_iter().enumerate() {
+        assert_eq!(script.wave(round).enemy_count(), count, "round {round}");
+    }
+}
+
+#[test]
+fn endless_gets_harder_within_bounds() {
+    let script = WaveScript::builtin();
+    let first = script.authored_rounds();
+
+    let mut previous = script.wave(first);
+    let mut coin_rains = 0;
+    for round in first + 1..first + 200 {
+        let wave = script.wave(round);
+        let timer = wave.enemies.iter().map(|spawn| spawn.timer).min().unwrap();
+        let previous_timer = previous
+            .enemies
+            .iter()
+            .map(|spawn| spawn.timer)
+            .min()
+            .unwrap();
+
+        assert!(wave.enemy_count() >= previous.enemy_count());
+        assert!(wave.enemy_count() <= 6);
+        assert!(timer <= previous_timer && timer >= 3);
+        if wave
+            .events
+            .iter()
+            .any(|event| matches!(event, WaveEvent::CoinRain { .. }))
+        {
+            coin_rains += 1;
+        }
+        previous = wave;
+    }
+
+    assert!(coin_rains > 0);
+}
+
+#[test]
+fn rounds_past_the_script_spawn() {
+    let mut world = World::new(
+        3,
+        &UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+    );
+    world.round = 40;
+    let balls = world.balls.len();
+
+    world.spawn_round();
+
+    assert!(world.balls.len() > balls);
+}
+
+#[test]
+fn invalid_scripts_are_rejected() {
+    let endless = r#""endless": {"first_enemies": 1, "enemies_every": 1, "max_enemies": 1,
+        "timer": 5, "timer_decay_every": 1, "min_timer": 1, "coins": {"min": 1, "max": 1},
+        "coin_rain_every": 0, "coin_rain": 0}"#;
+    let empty_coins = format!(r#"{{"rounds": [{{"coins": {{"min": 2, "max": 1}}}}], {endless}}}"#);
+    let no_timer = format!(
+        r#"{{"rounds": [{{"enemies": [{{"count": 1, "timer": 0}}], "coins": {{"min": 1, "max": 1}}}}], {endless}}}"#
+    );
+    let off_table = format!(
+        r#"{{"rounds": [{{"coins": {{"min": 1, "max": 1}}, "events": [{{"kind": "obstacle",
+            "obstacle": {{"kind": "post", "center": [1.5, 0.5], "radius": 0.02}}}}]}}], {endless}}}"#
+    );
+    let stuck = format!(
+        r#"{{"rounds": [], {}}}"#,
+        endless.replace("\"enemies_every\": 1", "\"enemies_every\": 0")
+    );
+
+    assert!(WaveScript::from_json(&format!(r#"{{"rounds": [], {endless}}}"#)).is_ok());
+    for json in [
+        empty_coins.as_str(),
+        no_timer.as_str(),
+        off_table.as_str(),
+        stuck.as_str(),
+        "not json",
+    ] {
+        assert!(WaveScript::from_json(json).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+use glam::Vec2;
+use std::collections::BTreeMap;
+use upsi5_core::{
+    entity::{BallId, BallType, PlayerId},
+    items::Item,
+    table::TableRegistry,
+    upgrades::UpgradeLevels,
+    world::{LaunchError, World},
+};
+
+fn first_player(world: &World) -> BallId {
+    world
+        .balls
+        .iter()
+        .map(|b| *b.borrow())
+        .find(|b| b.letypedelaboule == BallType::Player(PlayerId(0)))
+        .unwrap()
+        .id
+}
+
+fn play(seed: u64, shots: &[Vec2]) -> (Vec<Vec2>, Vec<Item>, u64) {
+    let mut world = World::new(
+        seed,
+        &UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+    );
+
+    for &shot in shots {
+        world
+            .launch_round(PlayerId(0), &BTreeMap::from([(first_player(&world), shot)]))
+            .unwrap();
+        while world.tick().0 {}
+        world.spawn_round();
+    }
+
+    let balls = world.balls.iter().map(|b| b.borrow().position).collect();
+
+    (balls, world.items.clone(), world.money)
+}
+
+#[test]
+fn same_seed_same_table() {
+    let shots = [Vec2::new(0.1, 0.03), Vec2::new(-0.05, 0.12)];
+
+    assert_eq!(play(42, &shots), play(42, &shots));
+}
+
+#[test]
+fn different_seed_different_coins() {
+    let mut a = World::new(
+        1,
+        &UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+    );
+    let mut b = World::new(
+        2,
+        &UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+    );
+
+    a.spawn_round();
+    b.spawn_round();
+
+    assert_ne!(a.items, b.items);
+}
+
+#[test]
+fn moves_follow_ids_not_indices() {
+    let mut world = World::new(
+        7,
+        &UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+    );
+    let player = first_player(&world);
+
+    world.balls.swap(0, 3);
+
+    world
+        .launch_round(PlayerId(0), &BTreeMap::from([(player, Vec2::new(0.1, 0.))]))
+        .unwrap();
+
+    assert!(world.ball(player).is_none());
+    assert_eq!(
+        world
+            .balls
+            .iter()
+            .filter(|b| b.borrow().letypedelaboule == BallType::Player(PlayerId(0)))
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn unknown_ball_is_rejected() {
+    let mut world = World::new(
+        7,
+        &UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+    );
+    let ball_count = world.balls.len();
+
+    assert_eq!(
+        world.launch_round(
+            PlayerId(0),
+            &BTreeMap::from([(BallId(999), Vec2::new(0.1, 0.))])
+        ),
+        Err(LaunchError::UnknownBall(BallId(999)))
+    );
+    assert_eq!(world.balls.len(), ball_count);
+}
+
+#[test]
+fn enemies_are_not_launched() {
+    let mut world = World::new(
+        7,
+        &UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+    );
+    let enemy = world
+        .balls
+        .iter()
+        .map(|b| *b.borrow())
+        .find(|b| !b.letypedelaboule.is_player())
+        .unwrap()
+        .id;
+    let ball_count = world.balls.len();
+
+    assert_eq!(
+        world.launch_round(
+            PlayerId(0),
+            &BTreeMap::from([
+                (first_player(&world), Vec2::new(0.1, 0.)),
+                (enemy, Vec2::new(0.1, 0.)),
+            ])
+        ),
+        Err(LaunchError::NotASlime(enemy))
+    );
+    assert_eq!(world.balls.len(), ball_count);
+}
+
+#[test]
+fn zero_aim_is_rejected() {
+    let mut world = World::new(
+        7,
+        &UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+    );
+    let player = first_player(&world);
+
+    assert_eq!(
+        world.launch_round(PlayerId(0), &BTreeMap::from([(player, Vec2::ZERO)])),
+        Err(LaunchError::ZeroAim(player))
+    );
+    assert!(world.ball(player).is_some());
+}
+
+#[test]
+fn undone_shots_play_out_the_same() {
+    let mut world = World::new(
+        11,
+        &UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+    );
+    let shot = BTreeMap::from([(first_player(&world), Vec2::new(0.1, 0.05))]);
+    let snapshot = world.snapshot();
+
+    world.launch_round(PlayerId(0), &shot).unwrap();
+    while world.tick().0 {}
+    world.spawn_round();
+    let first = play_state(&world);
+
+    world.restore(snapshot);
+    assert_eq!(world.round, 0);
+    assert_eq!(world.money, 0);
+    assert!(world.items.is_empty());
+
+    world.launch_round(PlayerId(0), &shot).unwrap();
+    while world.tick().0 {}
+    world.spawn_round();
+    assert_eq!(play_state(&world), first);
+}
+
+fn play_state(world: &World) -> (Vec<(BallId, Vec2)>, Vec<Item>, u64, usize) {
+    let balls = world
+        .balls
+        .iter()
+        .map(|b| (b.borrow().id, b.borrow().position))
+        .collect();
+    (balls, world.items.clone(), world.money, world.round)
+}
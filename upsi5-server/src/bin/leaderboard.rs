@@ -0,0 +1,54 @@
+//! Leaderboard server storing the scores in a local file, for development on localhost.
+//!
+//! ```text
+//! leaderboard [--port PORT] [--file PATH]
+//! ```
+
+use std::{env, net::TcpListener, process::ExitCode};
+
+use upsi5_core::leaderboard::DEFAULT_LEADERBOARD_PORT;
+use upsi5_server::leaderboard::{self, Leaderboard};
+
+fn main() -> ExitCode {
+    let mut port = DEFAULT_LEADERBOARD_PORT;
+    let mut file = "leaderboard.json".to_owned();
+
+    let mut args = env::args().skip(1);
+    while let Some(flag) = args.next() {
+        match (flag.as_str(), args.next()) {
+            ("--port", Some(value)) if value.parse::<u16>().is_ok() => {
+                port = value.parse().unwrap()
+            }
+            ("--file", Some(value)) => file = value,
+            _ => {
+                eprintln!("leaderboard: usage: leaderboard [--port PORT] [--file PATH]");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let leaderboard = match Leaderboard::open(&file) {
+        Ok(leaderboard) => leaderboard,
+        Err(e) => {
+            eprintln!("leaderboard: can't read {file}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("leaderboard: can't listen on port {port}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("leaderboard: listening on ws://127.0.0.1:{port}, scores kept in {file}");
+
+    match leaderboard::serve(listener, leaderboard) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("leaderboard: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Relay of online matches, for development on localhost.
+//!
+//! ```text
+//! relay [--port PORT] [--players N]
+//! ```
+
+use std::{
+    env,
+    net::TcpListener,
+    process::ExitCode,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use upsi5_core::online::DEFAULT_RELAY_PORT;
+use upsi5_server::relay::{self, Relay};
+
+fn main() -> ExitCode {
+    let mut port = DEFAULT_RELAY_PORT;
+    let mut players = 2;
+
+    let mut args = env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let value = args.next().and_then(|value| value.parse().ok());
+        match (flag.as_str(), value) {
+            ("--port", Some(value)) => port = value,
+            ("--players", Some(value)) if value > 0 && value <= u8::MAX.into() => {
+                players = value as u8
+            }
+            _ => {
+                eprintln!("relay: usage: relay [--port PORT] [--players N]");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("relay: can't listen on port {port}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("relay: listening on ws://127.0.0.1:{port}");
+
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_nanos() as u64);
+
+    match relay::serve(listener, Relay::new(seed, players)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("relay: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
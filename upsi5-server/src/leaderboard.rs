@@ -0,0 +1,315 @@
+//! Keeps the best score of each player in a local file. Submitted runs are played again from
+//! their replay before being ranked, so a client can't make up its score.
+
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    error::Error,
+    fmt, fs, io,
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tungstenite::Message;
+use upsi5_core::{
+    base64,
+    leaderboard::{LeaderboardRequest, LeaderboardResponse, MAX_NAME_LENGTH, MAX_TOP, Score},
+    replay::{Playback, Replay, ReplayError},
+};
+
+const FILE_VERSION: u64 = 2;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum VerifyError {
+    Name,
+    Replay(ReplayError),
+    /// The replay is of another run than the one claimed
+    Seed,
+    /// Only runs played alone are ranked
+    Multiplayer,
+    /// The run isn't over at the end of the replay
+    Unfinished,
+    /// The replay ends at another round or with another amount of money than claimed
+    Score {
+        round: usize,
+        money: u64,
+    },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name => write!(f, "names have 1 to {MAX_NAME_LENGTH} characters"),
+            Self::Replay(e) => write!(f, "{e}"),
+            Self::Seed => write!(f, "the replay has another seed"),
+            Self::Multiplayer => write!(f, "only runs played alone are ranked"),
+            Self::Unfinished => write!(f, "the replay stops before the end of the run"),
+            Self::Score { round, money } => write!(
+                f,
+                "the replay ends at round {round} with {money} coins, not the claimed score"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+impl From<ReplayError> for VerifyError {
+    fn from(e: ReplayError) -> Self {
+        Self::Replay(e)
+    }
+}
+
+/// Play the replay to its end and check it reaches the claimed score
+pub fn verify(score: &Score, replay: &[u8]) -> Result<(), VerifyError> {
+    let length = score.name.chars().count();
+    if length == 0 || length > MAX_NAME_LENGTH || score.name.trim() != score.name {
+        return Err(VerifyError::Name);
+    }
+
+    let replay = Replay::from_bytes(replay)?;
+    if replay.seed != score.seed {
+        return Err(VerifyError::Seed);
+    }
+    if replay.players != 1 {
+        return Err(VerifyError::Multiplayer);
+    }
+
+    let world = Playback::new(replay).run_to_end()?;
+    if !world.is_game_over() {
+        return Err(VerifyError::Unfinished);
+    }
+    if (world.round, world.money) != (score.round, score.money) {
+        return Err(VerifyError::Score {
+            round: world.round,
+            money: world.money,
+        });
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize)]
+struct LeaderboardFile {
+    version: u64,
+    scores: Vec<Score>,
+    #[serde(default, with = "base64::map")]
+    replays: BTreeMap<String, Vec<u8>>,
+}
+
+/// Best score of each player, best first
+pub struct Leaderboard {
+    scores: Vec<Score>,
+    /// Replay of the best run of each player, by name
+    replays: BTreeMap<String, Vec<u8>>,
+    /// Written after every change, None keeps the scores in memory only
+    path: Option<PathBuf>,
+}
+
+impl Leaderboard {
+    pub fn in_memory() -> Self {
+        Self {
+            scores: Vec::new(),
+            replays: BTreeMap::new(),
+            path: None,
+        }
+    }
+
+    /// Load the scores of the file, which is created on the first submission if missing
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let file = match fs::read_to_string(&path) {
+            Ok(json) => {
+                let mut value: Value = serde_json::from_str(&json).map_err(io::Error::other)?;
+                // Version 1 wrote the replays as arrays of numbers
+                if value["version"] == 1 {
+                    if value["replays"].is_object() {
+                        let replays: BTreeMap<String, Vec<u8>> =
+                            serde_json::from_value(value["replays"].take())
+                                .map_err(io::Error::other)?;
+                        value["replays"] = replays
+                            .into_iter()
+                            .map(|(name, replay)| (name, base64::encode(&replay).into()))
+                            .collect::<serde_json::Map<_, _>>()
+                            .into();
+                    }
+                    value["version"] = FILE_VERSION.into();
+                }
+
+                let file: LeaderboardFile =
+                    serde_json::from_value(value).map_err(io::Error::other)?;
+                if file.version != FILE_VERSION {
+                    return Err(io::Error::other(format!(
+                        "leaderboard version {} isn't {FILE_VERSION}",
+                        file.version
+                    )));
+                }
+                file
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => LeaderboardFile {
+                version: FILE_VERSION,
+                scores: Vec::new(),
+                replays: BTreeMap::new(),
+            },
+            Err(e) => return Err(e),
+        };
+
+        let mut leaderboard = Self {
+            scores: file.scores,
+            replays: file.replays,
+            path: Some(path),
+        };
+        leaderboard.sort();
+        Ok(leaderboard)
+    }
+
+    /// Keep the score and its replay if it's the best of its player, and return the player's rank
+    pub fn submit(&mut self, score: Score, replay: Vec<u8>) -> io::Result<usize> {
+        let best = match self.scores.iter_mut().find(|best| best.name == score.name) {
+            Some(best) if !score.beats(best) => false,
+            Some(best) => {
+                *best = score.clone();
+                true
+            }
+            None => {
+                self.scores.push(score.clone());
+                true
+            }
+        };
+        if best {
+            self.replays.insert(score.name.clone(), replay);
+        }
+        self.sort();
+        self.save()?;
+        Ok(self.rank(&score.name).unwrap().0)
+    }
+
+    pub fn top(&self, count: usize) -> &[Score] {
+        &self.scores[..count.min(self.scores.len())]
+    }
+
+    /// Rank of the player, starting at 1, and their best score
+    pub fn rank(&self, name: &str) -> Option<(usize, &Score)> {
+        self.scores
+            .iter()
+            .enumerate()
+            .find(|(_, score)| score.name == name)
+            .map(|(index, score)| (index + 1, score))
+    }
+
+    /// Replay of the best run of the player, missing for runs submitted before replays were kept
+    pub fn replay(&self, name: &str) -> Option<&[u8]> {
+        self.replays.get(name).map(Vec::as_slice)
+    }
+
+    fn sort(&mut self) {
+        self.scores
+            .sort_by_key(|score| Reverse((score.round, score.money)));
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let json = serde_json::to_string_pretty(&LeaderboardFile {
+            version: FILE_VERSION,
+            scores: self.scores.clone(),
+            replays: self.replays.clone(),
+        })
+        .map_err(io::Error::other)?;
+
+        // Written next to the file then moved over it, a crash never leaves half a leaderboard
+        let temporary = path.with_extension("tmp");
+        fs::write(&temporary, json)?;
+        fs::rename(temporary, path)
+    }
+}
+
+/// Answer a request, submissions are verified before the leaderboard is locked
+pub fn respond(
+    leaderboard: &Mutex<Leaderboard>,
+    request: LeaderboardRequest,
+) -> LeaderboardResponse {
+    match request {
+        LeaderboardRequest::Submit { score, replay } => {
+            if let Err(e) = verify(&score, &replay) {
+                return LeaderboardResponse::Rejected {
+                    reason: e.to_string(),
+                };
+            }
+            match leaderboard.lock().unwrap().submit(score, replay) {
+                Ok(rank) => LeaderboardResponse::Submitted { rank },
+                Err(e) => LeaderboardResponse::Error {
+                    message: format!("couldn't save the score: {e}"),
+                },
+            }
+        }
+        LeaderboardRequest::Top { count } => LeaderboardResponse::Top {
+            scores: leaderboard.lock().unwrap().top(count.min(MAX_TOP)).to_vec(),
+        },
+        LeaderboardRequest::Rank { name } => {
+            let leaderboard = leaderboard.lock().unwrap();
+            let rank = leaderboard.rank(&name);
+            LeaderboardResponse::Rank {
+                rank: rank.map(|(rank, _)| rank),
+                score: rank.map(|(_, score)| score.clone()),
+            }
+        }
+        LeaderboardRequest::Replay { name } => {
+            let leaderboard = leaderboard.lock().unwrap();
+            match (leaderboard.rank(&name), leaderboard.replay(&name)) {
+                (Some((_, score)), Some(replay)) => LeaderboardResponse::Replay {
+                    score: score.clone(),
+                    replay: replay.to_vec(),
+                },
+                _ => LeaderboardResponse::Error {
+                    message: format!("no run of {name} to replay"),
+                },
+            }
+        }
+    }
+}
+
+/// Accept websocket connections forever, each one is served by its own thread
+pub fn serve(listener: TcpListener, leaderboard: Leaderboard) -> io::Result<()> {
+    let leaderboard = Arc::new(Mutex::new(leaderboard));
+
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let leaderboard = leaderboard.clone();
+        thread::spawn(move || {
+            if let Err(e) = serve_connection(stream, &leaderboard) {
+                eprintln!("leaderboard: {e}");
+            }
+        });
+    }
+
+    Ok(())
+}
+
+fn serve_connection(
+    stream: TcpStream,
+    leaderboard: &Mutex<Leaderboard>,
+) -> Result<(), Box<dyn Error>> {
+    let mut socket = tungstenite::accept(stream)?;
+
+    loop {
+        let text = match socket.read() {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) | Err(tungstenite::Error::ConnectionClosed) => return Ok(()),
+            Ok(_) => continue,
+            Err(e) => return Err(e.into()),
+        };
+
+        let response = match LeaderboardRequest::from_json(&text) {
+            Ok(request) => respond(leaderboard, request),
+            Err(e) => LeaderboardResponse::Error {
+                message: e.to_string(),
+            },
+        };
+        socket.send(Message::text(response.to_json()))?;
+    }
+}
@@ -0,0 +1,292 @@
+//! Pairs players into matches and forwards their shots. The relay never simulates anything, it
+//! only waits for every player of a match before sending them the round, and compares the
+//! checksums they send back.
+
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    io,
+    net::{TcpListener, TcpStream},
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, Receiver, Sender},
+    },
+    thread,
+    time::Duration,
+};
+
+use tungstenite::{Message, WebSocket};
+use upsi5_core::{
+    entity::PlayerId,
+    online::{ClientMessage, MatchSetup, PROTOCOL_VERSION, ServerMessage, Shot},
+    rng::Rng,
+    table::TableRegistry,
+    upgrades::UpgradeLevels,
+};
+
+/// How long a connection waits for a message before sending the ones queued for it
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+pub type ConnectionId = u64;
+
+/// Messages to send, and who to send them to
+pub type Outbox = Vec<(ConnectionId, ServerMessage)>;
+
+struct Match {
+    /// Connection of each player, by player id
+    connections: Vec<ConnectionId>,
+    /// Shots sent so far for `next_round`
+    shots: BTreeMap<PlayerId, Vec<Shot>>,
+    /// Rounds before this one were already sent, only this one can be played
+    next_round: usize,
+    checksums: BTreeMap<usize, BTreeMap<PlayerId, u64>>,
+}
+
+impl Match {
+    fn broadcast(&self, message: &ServerMessage) -> Outbox {
+        self.connections
+            .iter()
+            .map(|&connection| (connection, message.clone()))
+            .collect()
+    }
+}
+
+pub struct Relay {
+    rng: Rng,
+    players: u8,
+    /// Connections waiting for a match, by room
+    rooms: BTreeMap<String, Vec<ConnectionId>>,
+    matches: BTreeMap<u64, Match>,
+    /// Match and player of each connection that is playing
+    seats: BTreeMap<ConnectionId, (u64, PlayerId)>,
+    next_match: u64,
+}
+
+impl Relay {
+    /// A relay starting matches of `players` players, their seeds are drawn from `seed`
+    pub fn new(seed: u64, players: u8) -> Self {
+        Self {
+            rng: Rng::new(seed),
+            players: players.max(1),
+            rooms: BTreeMap::new(),
+            matches: BTreeMap::new(),
+            seats: BTreeMap::new(),
+            next_match: 0,
+        }
+    }
+
+    pub fn handle(&mut self, from: ConnectionId, message: ClientMessage) -> Outbox {
+        match message {
+            ClientMessage::Join { room, version } => self.join(from, room, version),
+            ClientMessage::Moves { round, shots } => self.moves(from, round, shots),
+            ClientMessage::Checksum { round, checksum } => self.checksum(from, round, checksum),
+        }
+    }
+
+    /// Forget a connection, the other players of its match are told it left
+    pub fn disconnect(&mut self, connection: ConnectionId) -> Outbox {
+        for waiting in self.rooms.values_mut() {
+            waiting.retain(|&waiting| waiting != connection);
+        }
+        self.rooms.retain(|_, waiting| !waiting.is_empty());
+
+        let Some((match_id, player)) = self.seats.remove(&connection) else {
+            return Vec::new();
+        };
+        let Some(game) = self.matches.remove(&match_id) else {
+            return Vec::new();
+        };
+        for other in &game.connections {
+            self.seats.remove(other);
+        }
+
+        game.connections
+            .iter()
+            .filter(|&&other| other != connection)
+            .map(|&other| (other, ServerMessage::Left { player }))
+            .collect()
+    }
+
+    fn join(&mut self, from: ConnectionId, room: String, version: u32) -> Outbox {
+        if version != PROTOCOL_VERSION {
+            return error(
+                from,
+                format!("the relay speaks version {PROTOCOL_VERSION}, not {version}"),
+            );
+        }
+        if self.seats.contains_key(&from) || self.rooms.values().flatten().any(|&c| c == from) {
+            return error(from, "already joined".to_owned());
+        }
+
+        let waiting = self.rooms.entry(room.clone()).or_default();
+        waiting.push(from);
+        if waiting.len() < self.players as usize {
+            return Vec::new();
+        }
+        let connections = self.rooms.remove(&room).unwrap();
+
+        let setup = MatchSetup {
+            seed: self.rng.next_u64(),
+            levels: UpgradeLevels::default(),
+            table: TableRegistry::builtin().default_table().id.clone(),
+            players: self.players,
+        };
+        let match_id = self.next_match;
+        self.next_match += 1;
+
+        let mut outbox = Vec::new();
+        for (player, &connection) in connections.iter().enumerate() {
+            let you = PlayerId(player as u8);
+            self.seats.insert(connection, (match_id, you));
+            outbox.push((
+                connection,
+                ServerMessage::Start {
+                    setup: setup.clone(),
+                    you,
+                },
+            ));
+        }
+        self.matches.insert(
+            match_id,
+            Match {
+                connections,
+                shots: BTreeMap::new(),
+                next_round: 0,
+                checksums: BTreeMap::new(),
+            },
+        );
+        outbox
+    }
+
+    fn moves(&mut self, from: ConnectionId, round: usize, shots: Vec<Shot>) -> Outbox {
+        let Some(&(match_id, player)) = self.seats.get(&from) else {
+            return error(from, "not in a match".to_owned());
+        };
+        let game = self.matches.get_mut(&match_id).unwrap();
+
+        if round != game.next_round {
+            return error(from, format!("unexpected moves for round {round}"));
+        }
+        if game.shots.contains_key(&player) {
+            return error(from, format!("moves for round {round} already sent"));
+        }
+        game.shots.insert(player, shots);
+
+        if game.shots.len() < game.connections.len() {
+            return Vec::new();
+        }
+
+        // Kept apart by seat, the clients check each player only shot their own slimes
+        let shots = std::mem::take(&mut game.shots).into_values().collect();
+        game.next_round = round + 1;
+        game.broadcast(&ServerMessage::Round { round, shots })
+    }
+
+    fn checksum(&mut self, from: ConnectionId, round: usize, checksum: u64) -> Outbox {
+        let Some(&(match_id, player)) = self.seats.get(&from) else {
+            return error(from, "not in a match".to_owned());
+        };
+        let game = self.matches.get_mut(&match_id).unwrap();
+
+        if round >= game.next_round {
+            return error(from, format!("unexpected checksum for round {round}"));
+        }
+        let checksums = game.checksums.entry(round).or_default();
+        checksums.insert(player, checksum);
+        if checksums.len() < game.connections.len() {
+            return Vec::new();
+        }
+
+        let checksums = game.checksums.remove(&round).unwrap();
+        let mut values = checksums.values();
+        let first = values.next();
+        if values.all(|value| Some(value) == first) {
+            Vec::new()
+        } else {
+            game.broadcast(&ServerMessage::Desync { round })
+        }
+    }
+}
+
+fn error(to: ConnectionId, message: String) -> Outbox {
+    vec![(to, ServerMessage::Error { message })]
+}
+
+struct Shared {
+    relay: Relay,
+    senders: BTreeMap<ConnectionId, Sender<ServerMessage>>,
+}
+
+impl Shared {
+    fn dispatch(&self, outbox: Outbox) {
+        for (to, message) in outbox {
+            if let Some(sender) = self.senders.get(&to) {
+                // A closed receiver means the connection is already going away
+                let _ = sender.send(message);
+            }
+        }
+    }
+}
+
+/// Accept websocket connections forever, each one is served by its own thread
+pub fn serve(listener: TcpListener, relay: Relay) -> io::Result<()> {
+    let shared = Arc::new(Mutex::new(Shared {
+        relay,
+        senders: BTreeMap::new(),
+    }));
+
+    for (connection, stream) in (0..).zip(listener.incoming()) {
+        let stream = stream?;
+        let (sender, receiver) = mpsc::channel();
+        shared.lock().unwrap().senders.insert(connection, sender);
+
+        let shared = shared.clone();
+        thread::spawn(move || {
+            if let Err(e) = serve_connection(stream, connection, &shared, &receiver) {
+                eprintln!("relay: connection {connection}: {e}");
+            }
+
+            let mut shared = shared.lock().unwrap();
+            shared.senders.remove(&connection);
+            let outbox = shared.relay.disconnect(connection);
+            shared.dispatch(outbox);
+        });
+    }
+
+    Ok(())
+}
+
+fn serve_connection(
+    stream: TcpStream,
+    connection: ConnectionId,
+    shared: &Mutex<Shared>,
+    receiver: &Receiver<ServerMessage>,
+) -> Result<(), Box<dyn Error>> {
+    let mut socket: WebSocket<TcpStream> = tungstenite::accept(stream)?;
+    socket.get_ref().set_read_timeout(Some(POLL_INTERVAL))?;
+
+    loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => {
+                let outbox = match ClientMessage::from_json(&text) {
+                    Ok(message) => shared.lock().unwrap().relay.handle(connection, message),
+                    Err(e) => error(connection, e.to_string()),
+                };
+                shared.lock().unwrap().dispatch(outbox);
+            }
+            Ok(Message::Close(_)) => return Ok(()),
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) => {}
+            Err(tungstenite::Error::ConnectionClosed) => return Ok(()),
+            Err(e) => return Err(e.into()),
+        }
+
+        while let Ok(message) = receiver.try_recv() {
+            socket.send(Message::text(message.to_json()))?;
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use glam::Vec2;
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    net::{TcpListener, TcpStream},
+    sync::Mutex,
+    thread,
+    time::Duration,
+};
+use tungstenite::Message;
+use upsi5_core::{
+    balance::{Policy, RandomPolicy},
+    entity::{BallId, PlayerId},
+    leaderboard::{LeaderboardRequest, LeaderboardResponse, Score},
+    replay::{Replay, ReplayError},
+    table::TableRegistry,
+    upgrades::UpgradeLevels,
+    world::{LaunchError, World},
+};
+use upsi5_server::leaderboard::{self, Leaderboard, VerifyError};
+
+/// Play a whole run with random shots, and the score it ended with
+fn finished_run(name: &str, seed: u64) -> (Score, Replay) {
+    let mut replay = Replay::new(
+        seed,
+        UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+    );
+    let mut world = replay.world();
+    let mut policy = RandomPolicy::new(seed);
+
+    while !world.is_game_over() {
+        let moves = policy.moves(&world);
+        world.launch_round(PlayerId(0), &moves).unwrap();
+        replay.record(world.round, PlayerId(0), &moves);
+        while world.tick().0 {}
+        if !world.is_game_over() {
+            world.spawn_round();
+        }
+    }
+
+    let score = Score {
+        name: name.to_owned(),
+        round: world.round,
+        money: world.money,
+        seed,
+    };
+    (score, replay)
+}
+
+fn submit(score: &Score, replay: &Replay) -> LeaderboardRequest {
+    LeaderboardRequest::Submit {
+        score: score.clone(),
+        replay: replay.to_bytes(),
+    }
+}
+
+#[test]
+fn honest_runs_are_verified() {
+    let (score, replay) = finished_run("ada", 3);
+    assert_eq!(leaderboard::verify(&score, &replay.to_bytes()), Ok(()));
+}
+
+#[test]
+fn forged_scores_are_rejected() {
+    let (score, replay) = finished_run("ada", 3);
+    let bytes = replay.to_bytes();
+
+    let richer = Score {
+        money: score.money + 100,
+        ..score.clone()
+    };
+    assert!(matches!(
+        leaderboard::verify(&richer, &bytes),
+        Err(VerifyError::Score { .. })
+    ));
+
+    let longer = Score {
+        round: score.round + 1,
+        ..score.clone()
+    };
+    assert!(matches!(
+        leaderboard::verify(&longer, &bytes),
+        Err(VerifyError::Score { .. })
+    ));
+
+    let other_seed = Score {
+        seed: score.seed + 1,
+        ..score.clone()
+    };
+    assert_eq!(
+        leaderboard::verify(&other_seed, &bytes),
+        Err(VerifyError::Seed)
+    );
+
+    assert!(matches!(
+        leaderboard::verify(&score, &bytes[..bytes.len() / 2]),
+        Err(VerifyError::Replay(_))
+    ));
+
+    let unnamed = Score {
+        name: String::new(),
+        ..score.clone()
+    };
+    assert_eq!(
+        leaderboard::verify(&unnamed, &bytes),
+        Err(VerifyError::Name)
+    );
+}
+
+/// A replay whose first round launches `ball` with `aim`, which no client could have recorded
+fn forged_shot(seed: u64, ball: impl Fn(&World) -> BallId, aim: Vec2) -> (Score, Vec<u8>, BallId) {
+    let mut replay = Replay::new(
+        seed,
+        UpgradeLevels::default(),
+        TableRegistry::builtin().default_table(),
+    );
+    let ball = ball(&replay.world());
+    replay.record(0, PlayerId(0), &BTreeMap::from([(ball, aim)]));
+
+    let score = Score {
+        name: "eve".to_owned(),
+        round: 1,
+        money: 0,
+        seed,
+    };
+    (score, replay.to_bytes(), ball)
+}
+
+fn first_of(world: &World, slime: bool) -> BallId {
+    world
+        .balls
+        .iter()
+        .map(|b| *b.borrow())
+        .find(|b| b.letypedelaboule.is_player() == slime)
+        .unwrap()
+        .id
+}
+
+#[test]
+fn replays_launching_enemies_are_rejected() {
+    let (score, bytes, enemy) = forged_shot(3, |world| first_of(world, false), Vec2::new(0.1, 0.));
+
+    assert_eq!(
+        leaderboard::verify(&score, &bytes),
+        Err(VerifyError::Replay(ReplayError::Launch(
+            LaunchError::NotASlime(enemy)
+        )))
+    );
+}
+
+#[test]
+fn replays_aiming_past_the_drag_limit_are_rejected() {
+    let (score, bytes, slime) = forged_shot(3, |world| first_of(world, true), Vec2::new(3., 1.7));
+
+    assert_eq!(
+        leaderboard::verify(&score, &bytes),
+        Err(VerifyError::Replay(ReplayError::Launch(
+            LaunchError::AimTooLong(slime)
+        )))
+    );
+}
+
+#[test]
+fn unfinished_runs_are_rejected() {
+    let (score, mut replay) = finished_run("ada", 3);
+    replay.rounds.pop();
+    assert!(leaderboard::verify(&score, &replay.to_bytes()).is_err());
+}
+
+#[test]
+fn players_keep_their_best_score() {
+    let mut leaderboard = Leaderboard::in_memory();
+    let score = |name: &str, round, money| Score {
+        name: name.to_owned(),
+        round,
+        money,
+        seed: 0,
+    };
+
+    assert_eq!(
+        leaderboard.submit(score("ada", 5, 100), vec![1]).unwrap(),
+        1
+    );
+    assert_eq!(
+        leaderboard.submit(score("bob", 5, 300), vec![2]).unwrap(),
+        1
+    );
+    assert_eq!(leaderboard.submit(score("cy", 2, 900), vec![3]).unwrap(), 3);
+    // A worse run doesn't replace the best one
+    assert_eq!(leaderboard.submit(score("bob", 1, 0), vec![4]).unwrap(), 1);
+    assert_eq!(leaderboard.submit(score("ada", 7, 0), vec![5]).unwrap(), 1);
+
+    let names: Vec<&str> = leaderboard
+        .top(10)
+        .iter()
+        .map(|score| score.name.as_str())
+        .collect();
+    assert_eq!(names, ["ada", "bob", "cy"]);
+    assert_eq!(leaderboard.top(1).len(), 1);
+    assert_eq!(leaderboard.rank("bob").unwrap().0, 2);
+    assert_eq!(leaderboard.rank("bob").unwrap().1.money, 300);
+    assert!(leaderboard.rank("dee").is_none());
+
+    // Replays follow the best runs
+    assert_eq!(leaderboard.replay("ada"), Some(&[5][..]));
+    assert_eq!(leaderboard.replay("bob"), Some(&[2][..]));
+    assert_eq!(leaderboard.replay("dee"), None);
+}
+
+#[test]
+fn scores_are_kept_in_the_file() {
+    let path = env::temp_dir().join(format!("upsi5-leaderboard-{}.json", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    let (score, replay) = finished_run("ada", 3);
+    let board = Mutex::new(Leaderboard::open(&path).unwrap());
+    assert_eq!(
+        leaderboard::respond(&board, submit(&score, &replay)),
+        LeaderboardResponse::Submitted { rank: 1 }
+    );
+
+    let reopened = Leaderboard::open(&path).unwrap();
+    assert_eq!(reopened.top(10), [score]);
+    assert_eq!(reopened.replay("ada"), Some(&replay.to_bytes()[..]));
+
+    // Replays are written as text rather than arrays of numbers
+    let file: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert!(file["replays"]["ada"].is_string());
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn version_1_files_are_migrated() {
+    let path = env::temp_dir().join(format!("upsi5-leaderboard-v1-{}.json", std::process::id()));
+    fs::write(
+        &path,
+        r#"{"version": 1, "scores": [{"name": "ada", "round": 3, "money": 100, "seed": 7}],
+            "replays": {"ada": [83, 80, 82, 80]}}"#,
+    )
+    .unwrap();
+
+    let leaderboard = Leaderboard::open(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(leaderboard.rank("ada").unwrap().1.money, 100);
+    assert_eq!(leaderboard.replay("ada"), Some(&b"SPRP"[..]));
+}
+
+#[test]
+fn clients_submit_and_fetch_through_the_server() {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || leaderboard::serve(listener, Leaderboard::in_memory()));
+
+    let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(10)))
+        .unwrap();
+    let (mut socket, _) = tungstenite::client(format!("ws://127.0.0.1:{port}"), stream).unwrap();
+    let mut request = |request: LeaderboardRequest| {
+        socket.send(Message::text(request.to_json())).unwrap();
+        loop {
+            if let Message::Text(text) = socket.read().unwrap() {
+                return LeaderboardResponse::from_json(&text).unwrap();
+            }
+        }
+    };
+
+    let (honest, replay) = finished_run("ada", 3);
+    let forged = Score {
+        name: "bob".to_owned(),
+        money: honest.money + 1,
+        ..honest.clone()
+    };
+
+    assert_eq!(
+        request(submit(&honest, &replay)),
+        LeaderboardResponse::Submitted { rank: 1 }
+    );
+    assert!(matches!(
+        request(submit(&forged, &replay)),
+        LeaderboardResponse::Rejected { .. }
+    ));
+    assert_eq!(
+        request(LeaderboardRequest::Top { count: 10 }),
+        LeaderboardResponse::Top {
+            scores: vec![honest.clone()]
+        }
+    );
+    assert_eq!(
+        request(LeaderboardRequest::Rank {
+            name: "bob".to_owned()
+        }),
+        LeaderboardResponse::Rank {
+            rank: None,
+            score: None
+        }
+    );
+    assert_eq!(
+        request(LeaderboardRequest::Rank {
+            name: "ada".to_owned()
+        }),
+        LeaderboardResponse::Rank {
+            rank: Some(1),
+            score: Some(honest.clone())
+        }
+    );
+    assert_eq!(
+        request(LeaderboardRequest::Replay {
+            name: "ada".to_owned()
+        }),
+        LeaderboardResponse::Replay {
+            score: honest,
+            replay: replay.to_bytes()
+        }
+    );
+    assert!(matches!(
+        request(LeaderboardRequest::Replay {
+            name: "bob".to_owned()
+        }),
+        LeaderboardResponse::Error { .. }
+    ));
+}
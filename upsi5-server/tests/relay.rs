@@ -0,0 +1,322 @@
+use glam::Vec2;
+use std::{
+    collections::BTreeMap,
+    net::{TcpListener, TcpStream},
+    thread,
+    time::Duration,
+};
+use tungstenite::{Message, WebSocket};
+use upsi5_core::{
+    entity::{BallId, BallType, PlayerId},
+    online::{self, ClientMessage, PROTOCOL_VERSION, ServerMessage, Shot},
+};
+use upsi5_server::relay::{self, Relay};
+
+fn join(room: &str) -> ClientMessage {
+    ClientMessage::Join {
+        room: room.to_owned(),
+        version: PROTOCOL_VERSION,
+    }
+}
+
+/// A relay where connections 1 and 2 are playing together
+fn started() -> Relay {
+    let mut relay = Relay::new(5, 2);
+    relay.handle(1, join("room"));
+    relay.handle(2, join("room"));
+    relay
+}
+
+fn shot(ball: u64) -> Shot {
+    Shot::new(BallId(ball), Vec2::new(0.1, 0.))
+}
+
+/// Both players send their moves for `round`
+fn play(relay: &mut Relay, round: usize) {
+    for connection in [1, 2] {
+        relay.handle(
+            connection,
+            ClientMessage::Moves {
+                round,
+                shots: vec![],
+            },
+        );
+    }
+}
+
+#[test]
+fn matches_start_once_the_room_is_full() {
+    let mut relay = Relay::new(5, 2);
+
+    assert_eq!(relay.handle(1, join("room")), []);
+    assert_eq!(relay.handle(3, join("other")), []);
+    let outbox = relay.handle(2, join("room"));
+
+    let [
+        (
+            1,
+            ServerMessage::Start {
+                setup: first,
+                you: PlayerId(0),
+            },
+        ),
+        (
+            2,
+            ServerMessage::Start {
+                setup: second,
+                you: PlayerId(1),
+            },
+        ),
+    ] = &outbox[..]
+    else {
+        panic!("expected both players to start, got {outbox:?}");
+    };
+    assert_eq!(first, second);
+    assert_eq!(first.players, 2);
+}
+
+#[test]
+fn rounds_are_sent_once_everyone_played() {
+    let mut relay = started();
+
+    let outbox = relay.handle(
+        2,
+        ClientMessage::Moves {
+            round: 0,
+            shots: vec![shot(4)],
+        },
+    );
+    assert_eq!(outbox, []);
+
+    let outbox = relay.handle(
+        1,
+        ClientMessage::Moves {
+            round: 0,
+            shots: vec![shot(3)],
+        },
+    );
+    let round = ServerMessage::Round {
+        round: 0,
+        shots: vec![vec![shot(3)], vec![shot(4)]],
+    };
+    assert_eq!(outbox, [(1, round.clone()), (2, round)]);
+
+    // The round can't be played twice
+    let outbox = relay.handle(
+        1,
+        ClientMessage::Moves {
+            round: 0,
+            shots: vec![],
+        },
+    );
+    assert!(matches!(outbox[..], [(1, ServerMessage::Error { .. })]));
+}
+
+#[test]
+fn later_rounds_cant_be_played_ahead() {
+    let mut relay = started();
+
+    let outbox = relay.handle(
+        1,
+        ClientMessage::Moves {
+            round: 1,
+            shots: vec![shot(3)],
+        },
+    );
+    assert!(matches!(outbox[..], [(1, ServerMessage::Error { .. })]));
+
+    // The rejected moves don't hold up the round being played
+    relay.handle(
+        1,
+        ClientMessage::Moves {
+            round: 0,
+            shots: vec![shot(3)],
+        },
+    );
+    let outbox = relay.handle(
+        2,
+        ClientMessage::Moves {
+            round: 0,
+            shots: vec![shot(4)],
+        },
+    );
+    assert_eq!(outbox.len(), 2);
+}
+
+#[test]
+fn differing_checksums_are_reported() {
+    let mut relay = started();
+    play(&mut relay, 0);
+    play(&mut relay, 1);
+
+    let checksum = |round, checksum| ClientMessage::Checksum { round, checksum };
+    assert_eq!(relay.handle(1, checksum(0, 42)), []);
+    assert_eq!(relay.handle(2, checksum(0, 42)), []);
+
+    assert_eq!(relay.handle(1, checksum(1, 42)), []);
+    assert_eq!(
+        relay.handle(2, checksum(1, 43)),
+        [
+            (1, ServerMessage::Desync { round: 1 }),
+            (2, ServerMessage::Desync { round: 1 })
+        ]
+    );
+}
+
+#[test]
+fn checksums_cant_be_sent_ahead() {
+    let mut relay = started();
+    play(&mut relay, 0);
+
+    let outbox = relay.handle(
+        1,
+        ClientMessage::Checksum {
+            round: 1,
+            checksum: 42,
+        },
+    );
+    assert!(matches!(outbox[..], [(1, ServerMessage::Error { .. })]));
+
+    // The played round is still checked
+    relay.handle(
+        1,
+        ClientMessage::Checksum {
+            round: 0,
+            checksum: 42,
+        },
+    );
+    let outbox = relay.handle(
+        2,
+        ClientMessage::Checksum {
+            round: 0,
+            checksum: 43,
+        },
+    );
+    assert_eq!(outbox.len(), 2);
+}
+
+#[test]
+fn leaving_ends_the_match() {
+    let mut relay = started();
+
+    assert_eq!(
+        relay.disconnect(2),
+        [(
+            1,
+            ServerMessage::Left {
+                player: PlayerId(1)
+            }
+        )]
+    );
+
+    let outbox = relay.handle(
+        1,
+        ClientMessage::Moves {
+            round: 0,
+            shots: vec![],
+        },
+    );
+    assert!(matches!(outbox[..], [(1, ServerMessage::Error { .. })]));
+}
+
+#[test]
+fn other_versions_are_turned_away() {
+    let mut relay = Relay::new(5, 2);
+    let outbox = relay.handle(
+        1,
+        ClientMessage::Join {
+            room: "room".to_owned(),
+            version: PROTOCOL_VERSION + 1,
+        },
+    );
+    assert!(matches!(outbox[..], [(1, ServerMessage::Error { .. })]));
+}
+
+struct Client {
+    socket: WebSocket<TcpStream>,
+}
+
+impl Client {
+    fn connect(port: u16) -> Self {
+        let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .unwrap();
+        let (socket, _) = tungstenite::client(format!("ws://127.0.0.1:{port}"), stream).unwrap();
+        Self { socket }
+    }
+
+    fn send(&mut self, message: ClientMessage) {
+        self.socket.send(Message::text(message.to_json())).unwrap();
+    }
+
+    fn receive(&mut self) -> ServerMessage {
+        loop {
+            if let Message::Text(text) = self.socket.read().unwrap() {
+                return ServerMessage::from_json(&text).unwrap();
+            }
+        }
+    }
+}
+
+#[test]
+fn clients_play_a_match_through_the_relay() {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || relay::serve(listener, Relay::new(9, 2)));
+
+    let mut clients = [Client::connect(port), Client::connect(port)];
+    for client in &mut clients {
+        client.send(join("local"));
+    }
+
+    // Players are seated in the order the relay got their joins
+    let mut seats = Vec::new();
+    let mut worlds = Vec::new();
+    for client in &mut clients {
+        let ServerMessage::Start { setup, you } = client.receive() else {
+            panic!("expected the match to start");
+        };
+        seats.push(you);
+        worlds.push(setup.replay().unwrap().world());
+    }
+    let mut sorted = seats.clone();
+    sorted.sort();
+    assert_eq!(sorted, [PlayerId(0), PlayerId(1)]);
+
+    for ((client, world), &you) in clients.iter_mut().zip(&worlds).zip(&seats) {
+        let moves: BTreeMap<BallId, Vec2> = world
+            .balls
+            .iter()
+            .map(|b| *b.borrow())
+            .filter(|b| b.letypedelaboule == BallType::Player(you))
+            .map(|b| (b.id, Vec2::new(0.02, 0.08)))
+            .collect();
+        client.send(ClientMessage::Moves {
+            round: world.round,
+            shots: online::shots(&moves),
+        });
+    }
+
+    for (client, world) in clients.iter_mut().zip(&mut worlds) {
+        let ServerMessage::Round { round, shots } = client.receive() else {
+            panic!("expected the round");
+        };
+        assert_eq!(round, 0);
+        assert_eq!(shots.len(), 2);
+
+        online::launch_round(world, &shots).unwrap();
+        while world.tick().0 {}
+        world.spawn_round();
+        client.send(ClientMessage::Checksum {
+            round,
+            checksum: world.checksum(),
+        });
+    }
+    assert_eq!(worlds[0].checksum(), worlds[1].checksum());
+
+    // Nothing comes back for matching checksums, the next message is the other player leaving
+    let [mut first, second] = clients;
+    drop(second);
+    assert!(matches!(first.receive(), ServerMessage::Left { .. }));
+}